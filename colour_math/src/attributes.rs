@@ -2,7 +2,11 @@
 
 use normalised_angles::Degrees;
 
-use crate::{ColourComponent, ColourInterface, Hue, HueConstants, RGBConstants, RGB};
+use crate::{
+    gradient::{Gradient, HuePath, Interpolation},
+    temperature::CCT_RANGE,
+    ColourComponent, ColourInterface, Hue, HueConstants, RGBConstants, RGB,
+};
 
 pub mod drawing;
 pub mod hue_wheel;
@@ -36,6 +40,21 @@ pub trait ColourAttributeDisplayIfce<F: ColourComponent + Default> {
         vec![(RGB::BLACK, F::ZERO), (RGB::WHITE, F::ONE)]
     }
 
+    /// The gradient painted as the background (by default built from the colour stops)
+    fn gradient(&self) -> Gradient<F> {
+        Gradient::from(self.colour_stops())
+    }
+
+    /// The background colour at `posn` (sampled from the gradient)
+    fn background_rgb_at(&self, posn: F) -> RGB<F> {
+        self.gradient().sample(posn)
+    }
+
+    /// The label colour with the best contrast against the background behind the label
+    fn accessible_label_colour(&self) -> RGB<F> {
+        self.background_rgb_at(F::HALF).best_foreground_rgb()
+    }
+
     fn draw_attr_value_indicator(&self, drawer: &impl Draw<F>) {
         if let Some(attr_value) = self.attr_value() {
             let size = drawer.size();
@@ -86,7 +105,7 @@ pub trait ColourAttributeDisplayIfce<F: ColourComponent + Default> {
     fn draw_background(&self, drawer: &impl Draw<F>) {
         let posn = Point::<F>::default();
         let size = drawer.size();
-        drawer.paint_linear_gradient(posn, size, &self.colour_stops());
+        drawer.paint_linear_gradient(posn, size, &self.gradient());
     }
 
     fn draw_all(&self, drawer: &impl Draw<F>) {
@@ -157,7 +176,7 @@ where
     }
 
     fn label_colour(&self) -> RGB<F> {
-        RGB::WHITE
+        self.accessible_label_colour()
     }
 }
 
@@ -167,6 +186,48 @@ pub struct WarmthCAD<F: ColourComponent + Default> {
     target_warmth: Option<F>,
     warmth_fg_rgb: RGB<F>,
     target_warmth_fg_rgb: RGB<F>,
+    cct_colour_stops: bool,
+}
+
+impl<F: ColourComponent + Default> WarmthCAD<F> {
+    /// A warmth display whose background shows black body colours (from the top
+    /// to the bottom of `temperature::CCT_RANGE`) instead of cyan to red.  The
+    /// indicators then show correlated colour temperature and are omitted for colours
+    /// that don't have one (in that range).
+    pub fn with_cct_colour_stops() -> Self {
+        Self {
+            cct_colour_stops: true,
+            ..Self::new()
+        }
+    }
+
+    // The position of `kelvin` on the black body colour stops (evenly spaced in mireds
+    // (reciprocal megakelvin) from cool to warm).
+    fn cct_posn(kelvin: F) -> F {
+        let (min, max) = CCT_RANGE;
+        let (min_mired, max_mired) = (1.0e6 / max, 1.0e6 / min);
+        let mired = 1.0e6 / kelvin.to_f64().unwrap();
+        F::from((mired - min_mired) / (max_mired - min_mired))
+            .unwrap()
+            .max(F::ZERO)
+            .min(F::ONE)
+    }
+
+    fn kelvin_at(posn: f64) -> F {
+        let (min, max) = CCT_RANGE;
+        let (min_mired, max_mired) = (1.0e6 / max, 1.0e6 / min);
+        F::from(1.0e6 / (min_mired + posn * (max_mired - min_mired))).unwrap()
+    }
+
+    // Where `colour`'s indicator belongs on the current colour stops.  With black body
+    // colour stops this is its correlated colour temperature (if it has one).
+    fn warmth_posn(&self, colour: &impl ColourInterface<F>) -> Option<F> {
+        if self.cct_colour_stops {
+            colour.correlated_colour_temperature().map(Self::cct_posn)
+        } else {
+            Some(colour.warmth())
+        }
+    }
 }
 
 impl<F> ColourAttributeDisplayIfce<F> for WarmthCAD<F>
@@ -181,12 +242,13 @@ where
             target_warmth: None,
             warmth_fg_rgb: RGB::BLACK,
             target_warmth_fg_rgb: RGB::BLACK,
+            cct_colour_stops: false,
         }
     }
 
     fn set_colour(&mut self, colour: Option<&impl ColourInterface<F>>) {
         if let Some(colour) = colour {
-            self.warmth = Some(colour.warmth());
+            self.warmth = self.warmth_posn(colour);
             self.warmth_fg_rgb = colour.monochrome_rgb().best_foreground_rgb();
         } else {
             self.warmth = None;
@@ -204,7 +266,7 @@ where
 
     fn set_target_colour(&mut self, colour: Option<&impl ColourInterface<F>>) {
         if let Some(colour) = colour {
-            self.target_warmth = Some(colour.warmth());
+            self.target_warmth = self.warmth_posn(colour);
             self.target_warmth_fg_rgb = colour.monochrome_rgb().best_foreground_rgb();
         } else {
             self.target_warmth = None;
@@ -221,12 +283,22 @@ where
     }
 
     fn label_colour(&self) -> RGB<F> {
-        RGB::WHITE
+        self.accessible_label_colour()
     }
 
     fn colour_stops(&self) -> Vec<(RGB<F>, F)> {
-        let grey = RGB::WHITE * F::HALF;
-        vec![(RGB::CYAN, F::ZERO), (grey, F::HALF), (RGB::RED, F::ONE)]
+        if self.cct_colour_stops {
+            (0..=8)
+                .map(|i| {
+                    let posn = i as f64 / 8.0;
+                    let rgb = RGB::from_kelvin(Self::kelvin_at(posn)).expect("in range");
+                    (rgb, F::from(posn).unwrap())
+                })
+                .collect()
+        } else {
+            let grey = RGB::WHITE * F::HALF;
+            vec![(RGB::CYAN, F::ZERO), (grey, F::HALF), (RGB::RED, F::ONE)]
+        }
     }
 }
 
@@ -237,21 +309,22 @@ pub struct HueCAD<F: ColourComponent + Default> {
     hue_value: Option<F>,
     hue_fg_rgb: RGB<F>,
     target_hue_fg_rgb: RGB<F>,
-    colour_stops: Vec<(RGB<F>, F)>,
+    gradient: Gradient<F>,
 }
 
 impl<F: ColourComponent + Default> HueCAD<F> {
     fn set_colour_stops_for_hue(&mut self, hue: Hue<F>) {
         let mut stops = vec![];
         let mut hue = hue + Degrees::DEG_180;
-        let delta = Degrees::DEG_30;
-        for i in 0..13 {
-            let offset = F::from_usize(i).unwrap() / F::from(12.0).unwrap();
+        let delta = Degrees::DEG_90;
+        for i in 0..5 {
+            let offset = F::from_usize(i).unwrap() / F::from(4.0).unwrap();
             let rgb = hue.max_chroma_rgb();
             stops.push((rgb, offset));
             hue = hue - delta;
         }
-        self.colour_stops = stops
+        self.gradient =
+            Gradient::new(&stops).with_interpolation(Interpolation::HCV(HuePath::Shortest));
     }
 
     fn set_colour_stops(&mut self, colour: Option<&impl ColourInterface<F>>) {
@@ -260,7 +333,7 @@ impl<F: ColourComponent + Default> HueCAD<F> {
                 self.set_colour_stops_for_hue(hue);
             } else {
                 let grey = colour.rgb();
-                self.colour_stops = vec![(grey, F::ZERO), (grey, F::ONE)];
+                self.gradient = Gradient::new(&[(grey, F::ZERO), (grey, F::ONE)]);
             }
         } else {
             self.set_default_colour_stops();
@@ -269,7 +342,7 @@ impl<F: ColourComponent + Default> HueCAD<F> {
 
     fn set_default_colour_stops(&mut self) {
         let grey = RGB::WHITE * F::HALF;
-        self.colour_stops = vec![(grey, F::ZERO), (grey, F::ONE)];
+        self.gradient = Gradient::new(&[(grey, F::ZERO), (grey, F::ONE)]);
     }
 
     fn set_defaults_for_no_hue(&mut self) {
@@ -308,7 +381,7 @@ impl<F: ColourComponent + Default> ColourAttributeDisplayIfce<F> for HueCAD<F> {
             hue_value: None,
             hue_fg_rgb: RGB::BLACK,
             target_hue_fg_rgb: RGB::BLACK,
-            colour_stops: vec![(grey, F::ZERO), (grey, F::ONE)],
+            gradient: Gradient::new(&[(grey, F::ZERO), (grey, F::ONE)]),
         }
     }
 
@@ -369,7 +442,11 @@ impl<F: ColourComponent + Default> ColourAttributeDisplayIfce<F> for HueCAD<F> {
     }
 
     fn colour_stops(&self) -> Vec<(RGB<F>, F)> {
-        self.colour_stops.clone()
+        self.gradient.colour_stops()
+    }
+
+    fn gradient(&self) -> Gradient<F> {
+        self.gradient.clone()
     }
 }
 
@@ -479,7 +556,7 @@ where
     }
 
     fn label_colour(&self) -> RGB<F> {
-        RGB::WHITE
+        self.accessible_label_colour()
     }
 
     fn colour_stops(&self) -> Vec<(RGB<F>, F)> {
@@ -593,10 +670,41 @@ where
     }
 
     fn label_colour(&self) -> RGB<F> {
-        RGB::WHITE
+        self.accessible_label_colour()
     }
 
     fn colour_stops(&self) -> Vec<(RGB<F>, F)> {
         self.colour_stops.clone()
     }
 }
+
+#[cfg(test)]
+mod attributes_tests {
+    use super::*;
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn warmth_cct_indicator() {
+        let mut cad = WarmthCAD::<f64>::with_cct_colour_stops();
+        let stops = cad.colour_stops();
+        // McCamy's approximation puts the end points just outside `CCT_RANGE`
+        for (rgb, posn) in stops[1..stops.len() - 1].iter() {
+            let kelvin = WarmthCAD::<f64>::kelvin_at(*posn);
+            assert_eq!(RGB::from_kelvin(kelvin), Some(*rgb));
+            cad.set_colour(Some(rgb));
+            assert_approx_eq!(cad.attr_value().unwrap(), *posn, 0.01);
+            cad.set_target_colour(Some(rgb));
+            assert_approx_eq!(cad.attr_target_value().unwrap(), *posn, 0.01);
+        }
+        for kelvin in [2_500.0, 4_000.0, 6_500.0, 10_000.0].iter() {
+            cad.set_colour(RGB::from_kelvin(*kelvin).as_ref());
+            let expected = WarmthCAD::<f64>::cct_posn(*kelvin);
+            assert_approx_eq!(cad.attr_value().unwrap(), expected, 0.01);
+        }
+        cad.set_colour(Some(&RGB::<f64>::GREEN));
+        assert_eq!(cad.attr_value(), None);
+        let mut cad = WarmthCAD::<f64>::new();
+        cad.set_colour(Some(&RGB::<f64>::GREEN));
+        assert_eq!(cad.attr_value(), Some(RGB::<f64>::GREEN.warmth()));
+    }
+}
@@ -4,7 +4,7 @@ use std::default::Default;
 use normalised_angles::Degrees;
 use num_traits_plus::float_plus::FloatPlus;
 
-use crate::{ColourComponent, RGB};
+use crate::{gradient::Gradient, ColourComponent, RGB};
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Point<F: FloatPlus + Default> {
@@ -104,7 +104,7 @@ pub trait Draw<F: ColourComponent + Default> {
     fn set_line_width(&self, width: F);
     fn set_text_colour(&self, rgb: RGB<F>);
     fn draw_line(&self, line: &[Point<F>]);
-    fn paint_linear_gradient(&self, posn: Point<F>, size: Size<F>, colour_stops: &[(RGB<F>, F)]);
+    fn paint_linear_gradient(&self, posn: Point<F>, size: Size<F>, gradient: &Gradient<F>);
     fn draw_polygon(&self, polygon: &[Point<F>], fill: bool);
     fn draw_text(&self, text: &str, posn: TextPosn<F>, font_size: F);
 
@@ -0,0 +1,670 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use num_traits_plus::float_plus::*;
+
+use crate::{
+    clamped,
+    illuminant::{ChromaticAdaptation, Illuminant},
+    linear_rgb::LinearRGB,
+    ColourComponent, HueConstants, RGBConstants, RGB,
+};
+
+/// sRGB (linear) to XYZ derived from the sRGB primaries and the D65 white point.
+pub(crate) const SRGB_TO_XYZ: [[f64; 3]; 3] = [
+    [
+        0.412_390_799_265_959_5,
+        0.357_584_339_383_878,
+        0.180_480_788_401_834_3,
+    ],
+    [
+        0.212_639_005_871_510_36,
+        0.715_168_678_767_755_9,
+        0.072_192_315_360_733_71,
+    ],
+    [
+        0.019_330_818_715_591_85,
+        0.119_194_779_794_626,
+        0.950_532_152_249_660_6,
+    ],
+];
+
+pub(crate) const XYZ_TO_SRGB: [[f64; 3]; 3] = [
+    [
+        3.240_969_941_904_521_3,
+        -1.537_383_177_570_093_5,
+        -0.498_610_760_293_003_3,
+    ],
+    [
+        -0.969_243_636_280_879_8,
+        1.875_967_501_507_720_6,
+        0.041_555_057_407_175_61,
+    ],
+    [
+        0.055_630_079_696_993_61,
+        -0.203_976_958_888_976_57,
+        1.056_971_514_242_878_6,
+    ],
+];
+
+/// The XYZ coordinates of the D65 white point (with Y normalised to 1).
+pub(crate) const D65: [f64; 3] = [0.950_455_927_051_671_7, 1.0, 1.089_057_750_759_878_4];
+
+pub(crate) fn apply_matrix<F: ColourComponent>(matrix: &[[f64; 3]; 3], v: [F; 3]) -> [F; 3] {
+    let mut result = [F::ZERO, F::ZERO, F::ZERO];
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, k) in row.iter().enumerate() {
+            result[i] += F::from(*k).unwrap() * v[j];
+        }
+    }
+    result
+}
+
+// CIE standard values for the piecewise Lab function
+fn epsilon<F: ColourComponent>() -> F {
+    F::from(216.0 / 24389.0).unwrap()
+}
+
+fn kappa<F: ColourComponent>() -> F {
+    F::from(24389.0 / 27.0).unwrap()
+}
+
+fn lab_f<F: ColourComponent>(t: F) -> F {
+    if t > epsilon() {
+        t.cbrt()
+    } else {
+        (kappa::<F>() * t + F::from(16.0).unwrap()) / F::from(116.0).unwrap()
+    }
+}
+
+fn lab_f_inverse<F: ColourComponent>(f: F) -> F {
+    let cubed = f.powi(3);
+    if cubed > epsilon() {
+        cubed
+    } else {
+        (F::from(116.0).unwrap() * f - F::from(16.0).unwrap()) / kappa::<F>()
+    }
+}
+
+/// CIE 1931 XYZ tristimulus values (D65 white has Y == 1).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct XYZ<F: ColourComponent> {
+    pub(crate) x: F,
+    pub(crate) y: F,
+    pub(crate) z: F,
+}
+
+impl<F: ColourComponent> XYZ<F> {
+    pub fn x(&self) -> F {
+        self.x
+    }
+
+    pub fn y(&self) -> F {
+        self.y
+    }
+
+    pub fn z(&self) -> F {
+        self.z
+    }
+
+    /// The XYZ coordinates of an sRGB colour relative to the `white` reference white
+    /// rather than D65 (using the Bradford transform).
+    pub fn from_rgb_with_white(rgb: &RGB<F>, white: Illuminant) -> Self {
+        ChromaticAdaptation::default().adapt(&rgb.into(), Illuminant::D65, white)
+    }
+
+    /// The sRGB colour whose XYZ coordinates relative to the `white` reference white
+    /// are these.  Colours outside the sRGB gamut are clipped to it.
+    pub fn rgb_with_white(&self, white: Illuminant) -> RGB<F> {
+        ChromaticAdaptation::default()
+            .adapt(self, white, Illuminant::D65)
+            .into()
+    }
+
+    /// These coordinates (relative to `source`) transformed to be relative to `destination`.
+    pub fn adapted(
+        &self,
+        source: Illuminant,
+        destination: Illuminant,
+        method: ChromaticAdaptation,
+    ) -> Self {
+        method.adapt(self, source, destination)
+    }
+}
+
+/// CIE 1976 L*a*b* where L* is in the range 0.0 to 100.0.  Unless otherwise stated
+/// conversions are relative to the D65 reference white.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Lab<F: ColourComponent> {
+    pub(crate) l: F,
+    pub(crate) a: F,
+    pub(crate) b: F,
+}
+
+impl<F: ColourComponent> Lab<F> {
+    pub fn l(&self) -> F {
+        self.l
+    }
+
+    pub fn a(&self) -> F {
+        self.a
+    }
+
+    pub fn b(&self) -> F {
+        self.b
+    }
+
+    /// L*a*b* for XYZ coordinates that are relative to the `white` reference white.
+    pub fn from_xyz_with_white(xyz: &XYZ<F>, white: Illuminant) -> Self {
+        let white_point = white.white_point_array();
+        let fx = lab_f(xyz.x / F::from(white_point[0]).unwrap());
+        let fy = lab_f(xyz.y / F::from(white_point[1]).unwrap());
+        let fz = lab_f(xyz.z / F::from(white_point[2]).unwrap());
+        Self {
+            l: F::from(116.0).unwrap() * fy - F::from(16.0).unwrap(),
+            a: F::from(500.0).unwrap() * (fx - fy),
+            b: F::from(200.0).unwrap() * (fy - fz),
+        }
+    }
+
+    /// L*a*b* relative to the `white` reference white for an sRGB (D65) colour
+    /// e.g. to predict the appearance of a screen colour printed and viewed under D50.
+    pub fn from_rgb_with_white(rgb: &RGB<F>, white: Illuminant) -> Self {
+        Self::from_xyz_with_white(&XYZ::from_rgb_with_white(rgb, white), white)
+    }
+
+    /// The XYZ coordinates of this colour when it is interpreted as being relative to
+    /// the `white` reference white.
+    pub fn xyz_with_white(&self, white: Illuminant) -> XYZ<F> {
+        let white_point = white.white_point_array();
+        let fy = (self.l + F::from(16.0).unwrap()) / F::from(116.0).unwrap();
+        let fx = fy + self.a / F::from(500.0).unwrap();
+        let fz = fy - self.b / F::from(200.0).unwrap();
+        let y = if self.l > kappa::<F>() * epsilon::<F>() {
+            fy.powi(3)
+        } else {
+            self.l / kappa::<F>()
+        };
+        XYZ {
+            x: lab_f_inverse(fx) * F::from(white_point[0]).unwrap(),
+            y: y * F::from(white_point[1]).unwrap(),
+            z: lab_f_inverse(fz) * F::from(white_point[2]).unwrap(),
+        }
+    }
+
+    /// The sRGB (D65) colour for this colour when it is interpreted as being relative
+    /// to the `white` reference white.
+    pub fn rgb_with_white(&self, white: Illuminant) -> RGB<F> {
+        self.xyz_with_white(white).rgb_with_white(white)
+    }
+}
+
+/// Cylindrical form of L*a*b* with the hue angle in degrees (0.0 to 360.0).
+/// The hue of a grey is reported as 0.0.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct LCh<F: ColourComponent> {
+    pub(crate) l: F,
+    pub(crate) c: F,
+    pub(crate) h: F,
+}
+
+impl<F: ColourComponent> LCh<F> {
+    pub fn l(&self) -> F {
+        self.l
+    }
+
+    pub fn c(&self) -> F {
+        self.c
+    }
+
+    pub fn h(&self) -> F {
+        self.h
+    }
+}
+
+macro_rules! impl_cie_constants {
+    ( $f:ty ) => {
+        impl HueConstants for XYZ<$f> {
+            const RED: Self = Self {
+                x: 0.412_390_799_265_959_5_f64 as $f,
+                y: 0.212_639_005_871_510_36_f64 as $f,
+                z: 0.019_330_818_715_591_85_f64 as $f,
+            };
+            const GREEN: Self = Self {
+                x: 0.357_584_339_383_878_f64 as $f,
+                y: 0.715_168_678_767_755_9_f64 as $f,
+                z: 0.119_194_779_794_626_f64 as $f,
+            };
+            const BLUE: Self = Self {
+                x: 0.180_480_788_401_834_3_f64 as $f,
+                y: 0.072_192_315_360_733_71_f64 as $f,
+                z: 0.950_532_152_249_660_6_f64 as $f,
+            };
+
+            const CYAN: Self = Self {
+                x: 0.538_065_127_785_712_2_f64 as $f,
+                y: 0.787_360_994_128_489_6_f64 as $f,
+                z: 1.069_726_932_044_286_6_f64 as $f,
+            };
+            const MAGENTA: Self = Self {
+                x: 0.592_871_587_667_793_8_f64 as $f,
+                y: 0.284_831_321_232_244_1_f64 as $f,
+                z: 0.969_862_970_965_252_4_f64 as $f,
+            };
+            const YELLOW: Self = Self {
+                x: 0.769_975_138_649_837_5_f64 as $f,
+                y: 0.927_807_684_639_266_3_f64 as $f,
+                z: 0.138_525_598_510_217_84_f64 as $f,
+            };
+        }
+
+        impl RGBConstants for XYZ<$f> {
+            const WHITE: Self = Self {
+                x: 0.950_455_927_051_671_7_f64 as $f,
+                y: 1.0,
+                z: 1.089_057_750_759_878_4_f64 as $f,
+            };
+            const BLACK: Self = Self {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+        }
+
+        impl HueConstants for Lab<$f> {
+            const RED: Self = Self {
+                l: 53.237_115_595_429_37_f64 as $f,
+                a: 80.090_113_523_103_8_f64 as $f,
+                b: 67.203_263_511_722_14_f64 as $f,
+            };
+            const GREEN: Self = Self {
+                l: 87.735_519_109_66_f64 as $f,
+                a: -86.181_596_890_398_95_f64 as $f,
+                b: 83.186_620_273_629_98_f64 as $f,
+            };
+            const BLUE: Self = Self {
+                l: 32.300_872_903_980_18_f64 as $f,
+                a: 79.195_270_307_404_2_f64 as $f,
+                b: -107.855_465_539_742_65_f64 as $f,
+            };
+
+            const CYAN: Self = Self {
+                l: 91.114_752_316_705_36_f64 as $f,
+                a: -48.078_888_386_977_326_f64 as $f,
+                b: -14.128_985_262_449_456_f64 as $f,
+            };
+            const MAGENTA: Self = Self {
+                l: 60.322_731_354_551_394_f64 as $f,
+                a: 98.237_443_813_184_27_f64 as $f,
+                b: -60.828_910_231_043_906_f64 as $f,
+            };
+            const YELLOW: Self = Self {
+                l: 97.138_559_341_796_99_f64 as $f,
+                a: -21.559_970_814_535_035_f64 as $f,
+                b: 94.483_840_015_570_11_f64 as $f,
+            };
+        }
+
+        impl RGBConstants for Lab<$f> {
+            const WHITE: Self = Self {
+                l: 100.0,
+                a: 0.0,
+                b: 0.0,
+            };
+            const BLACK: Self = Self {
+                l: 0.0,
+                a: 0.0,
+                b: 0.0,
+            };
+        }
+
+        impl HueConstants for LCh<$f> {
+            const RED: Self = Self {
+                l: 53.237_115_595_429_37_f64 as $f,
+                c: 104.550_011_529_265_83_f64 as $f,
+                h: 39.999_865_154_398_15_f64 as $f,
+            };
+            const GREEN: Self = Self {
+                l: 87.735_519_109_66_f64 as $f,
+                c: 119.780_137_899_103_82_f64 as $f,
+                h: 136.013_068_685_014_93_f64 as $f,
+            };
+            const BLUE: Self = Self {
+                l: 32.300_872_903_980_18_f64 as $f,
+                c: 133.808_416_349_112_5_f64 as $f,
+                h: 306.288_803_257_293_24_f64 as $f,
+            };
+
+            const CYAN: Self = Self {
+                l: 91.114_752_316_705_36_f64 as $f,
+                c: 50.111_951_998_240_27_f64 as $f,
+                h: 196.376_526_472_440_3_f64 as $f,
+            };
+            const MAGENTA: Self = Self {
+                l: 60.322_731_354_551_394_f64 as $f,
+                c: 115.545_452_904_235_63_f64 as $f,
+                h: 328.234_088_253_577_2_f64 as $f,
+            };
+            const YELLOW: Self = Self {
+                l: 97.138_559_341_796_99_f64 as $f,
+                c: 96.912_477_863_335_28_f64 as $f,
+                h: 102.854_059_693_977_89_f64 as $f,
+            };
+        }
+
+        impl RGBConstants for LCh<$f> {
+            const WHITE: Self = Self {
+                l: 100.0,
+                c: 0.0,
+                h: 0.0,
+            };
+            const BLACK: Self = Self {
+                l: 0.0,
+                c: 0.0,
+                h: 0.0,
+            };
+        }
+    };
+}
+
+impl_cie_constants!(f32);
+impl_cie_constants!(f64);
+
+impl<F: ColourComponent> FloatApproxEq<F> for XYZ<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.x.approx_eq(&other.x, max_diff)
+            && self.y.approx_eq(&other.y, max_diff)
+            && self.z.approx_eq(&other.z, max_diff)
+    }
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for Lab<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.l.approx_eq(&other.l, max_diff)
+            && self.a.approx_eq(&other.a, max_diff)
+            && self.b.approx_eq(&other.b, max_diff)
+    }
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for LCh<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.l.approx_eq(&other.l, max_diff)
+            && self.c.approx_eq(&other.c, max_diff)
+            && self.h.approx_eq(&other.h, max_diff)
+    }
+}
+
+impl<F: ColourComponent> From<[F; 3]> for XYZ<F> {
+    fn from(array: [F; 3]) -> Self {
+        Self {
+            x: array[0],
+            y: array[1],
+            z: array[2],
+        }
+    }
+}
+
+impl<F: ColourComponent> From<[F; 3]> for Lab<F> {
+    fn from(array: [F; 3]) -> Self {
+        Self {
+            l: array[0],
+            a: array[1],
+            b: array[2],
+        }
+    }
+}
+
+impl<F: ColourComponent> From<[F; 3]> for LCh<F> {
+    fn from(array: [F; 3]) -> Self {
+        debug_assert!(array[1] >= F::ZERO, "{:?}", array);
+        Self {
+            l: array[0],
+            c: array[1],
+            h: array[2],
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for XYZ<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        let linear_rgb = LinearRGB::from(rgb);
+        apply_matrix(&SRGB_TO_XYZ, linear_rgb.0).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for XYZ<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+/// Colours outside the sRGB gamut are clipped to it.
+impl<F: ColourComponent> From<&XYZ<F>> for RGB<F> {
+    fn from(xyz: &XYZ<F>) -> Self {
+        let mut array = apply_matrix(&XYZ_TO_SRGB, [xyz.x, xyz.y, xyz.z]);
+        for component in array.iter_mut() {
+            *component = clamped(*component);
+        }
+        LinearRGB::from(array).into()
+    }
+}
+
+impl<F: ColourComponent> From<XYZ<F>> for RGB<F> {
+    fn from(xyz: XYZ<F>) -> Self {
+        (&xyz).into()
+    }
+}
+
+impl<F: ColourComponent> From<&XYZ<F>> for Lab<F> {
+    fn from(xyz: &XYZ<F>) -> Self {
+        Self::from_xyz_with_white(xyz, Illuminant::D65)
+    }
+}
+
+impl<F: ColourComponent> From<&Lab<F>> for XYZ<F> {
+    fn from(lab: &Lab<F>) -> Self {
+        lab.xyz_with_white(Illuminant::D65)
+    }
+}
+
+impl<F: ColourComponent> From<&Lab<F>> for LCh<F> {
+    fn from(lab: &Lab<F>) -> Self {
+        let c = lab.a.hypot(lab.b);
+        let h = if c == F::ZERO {
+            F::ZERO
+        } else {
+            let degrees = lab.b.atan2(lab.a).to_degrees();
+            if degrees < F::ZERO {
+                degrees + F::DEG_360
+            } else {
+                degrees
+            }
+        };
+        Self { l: lab.l, c, h }
+    }
+}
+
+impl<F: ColourComponent> From<&LCh<F>> for Lab<F> {
+    fn from(lch: &LCh<F>) -> Self {
+        let radians = lch.h.to_radians();
+        Self {
+            l: lch.l,
+            a: lch.c * radians.cos(),
+            b: lch.c * radians.sin(),
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for Lab<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        (&XYZ::from(rgb)).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for Lab<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&Lab<F>> for RGB<F> {
+    fn from(lab: &Lab<F>) -> Self {
+        XYZ::from(lab).into()
+    }
+}
+
+impl<F: ColourComponent> From<Lab<F>> for RGB<F> {
+    fn from(lab: Lab<F>) -> Self {
+        (&lab).into()
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for LCh<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        (&Lab::from(rgb)).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for LCh<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&LCh<F>> for RGB<F> {
+    fn from(lch: &LCh<F>) -> Self {
+        Lab::from(lch).into()
+    }
+}
+
+impl<F: ColourComponent> From<LCh<F>> for RGB<F> {
+    fn from(lch: LCh<F>) -> Self {
+        (&lch).into()
+    }
+}
+
+impl_colour_interface_via_rgb!(XYZ, {
+    fn xyz(&self) -> XYZ<F> {
+        *self
+    }
+
+    fn lab(&self) -> Lab<F> {
+        self.into()
+    }
+
+    fn lch(&self) -> LCh<F> {
+        (&Lab::from(self)).into()
+    }
+});
+
+impl_colour_interface_via_rgb!(Lab, {
+    fn xyz(&self) -> XYZ<F> {
+        self.into()
+    }
+
+    fn lab(&self) -> Lab<F> {
+        *self
+    }
+
+    fn lch(&self) -> LCh<F> {
+        self.into()
+    }
+});
+
+impl_colour_interface_via_rgb!(LCh, {
+    fn xyz(&self) -> XYZ<F> {
+        (&Lab::from(self)).into()
+    }
+
+    fn lab(&self) -> Lab<F> {
+        self.into()
+    }
+
+    fn lch(&self) -> LCh<F> {
+        *self
+    }
+});
+
+#[cfg(test)]
+mod cie_tests {
+    use super::*;
+    use crate::ColourInterface;
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn constants_match_conversions() {
+        for (rgb, xyz) in RGB::<f64>::PRIMARIES
+            .iter()
+            .zip(XYZ::<f64>::PRIMARIES.iter())
+        {
+            assert_approx_eq!(XYZ::from(rgb), *xyz, 0.000_000_001);
+        }
+        for (rgb, lab) in RGB::<f64>::SECONDARIES
+            .iter()
+            .zip(Lab::<f64>::SECONDARIES.iter())
+        {
+            assert_approx_eq!(Lab::from(rgb), *lab, 0.000_000_001);
+        }
+        for (rgb, lch) in RGB::<f64>::PRIMARIES
+            .iter()
+            .zip(LCh::<f64>::PRIMARIES.iter())
+        {
+            assert_approx_eq!(LCh::from(rgb), *lch, 0.000_000_001);
+        }
+        for (rgb, lab) in RGB::<f64>::GREYS.iter().zip(Lab::<f64>::GREYS.iter()) {
+            assert_approx_eq!(Lab::from(rgb), *lab, 0.000_000_001);
+            assert_eq!(LCh::from(rgb).h(), 0.0);
+        }
+        assert_approx_eq!(XYZ::from(RGB::<f32>::WHITE), XYZ::<f32>::WHITE, 0.000_01);
+        assert_approx_eq!(Lab::from(RGB::<f32>::RED), Lab::<f32>::RED, 0.000_01);
+    }
+
+    #[test]
+    fn round_trips() {
+        let values = [0.0, 0.001, 0.01, 0.2, 0.5, 0.8, 0.99, 1.0];
+        for red in values.iter() {
+            for green in values.iter() {
+                for blue in values.iter() {
+                    let rgb = RGB::<f64>::from([*red, *green, *blue]);
+                    assert_approx_eq!(RGB::from(XYZ::from(rgb)), rgb, 0.000_000_001);
+                    assert_approx_eq!(RGB::from(Lab::from(rgb)), rgb, 0.000_000_001);
+                    assert_approx_eq!(RGB::from(LCh::from(rgb)), rgb, 0.000_000_001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn colour_interface() {
+        let lab = Lab::<f64>::from([50.0, 20.0, -30.0]);
+        assert_approx_eq!(lab.lab(), lab);
+        assert_approx_eq!(lab.lch().lab(), lab, 0.000_000_001);
+        assert_approx_eq!(lab.xyz().lab(), lab, 0.000_000_001);
+        assert_eq!(lab.hcv(), lab.rgb().hcv());
+        assert_approx_eq!(Lab::<f64>::WHITE.rgb(), RGB::WHITE, 0.000_000_001);
+        assert_approx_eq!(LCh::<f64>::YELLOW.value(), 2.0 / 3.0, 0.000_000_001);
+        assert_approx_eq!(RGB::<f64>::CYAN.lch(), LCh::CYAN, 0.000_000_001);
+    }
+
+    #[test]
+    fn white_point_parameter() {
+        for white in crate::illuminant::Illuminant::ALL.iter() {
+            let xyz = XYZ::<f64>::from_rgb_with_white(&RGB::WHITE, *white);
+            assert_approx_eq!(xyz, white.white_point(), 0.000_000_001);
+            let lab = Lab::<f64>::from_rgb_with_white(&RGB::WHITE, *white);
+            assert_approx_eq!(lab, Lab::from([100.0, 0.0, 0.0]), 0.000_000_001);
+            let rgb = RGB::<f64>::from([0.6, 0.3, 0.2]);
+            let lab = Lab::from_rgb_with_white(&rgb, *white);
+            assert_approx_eq!(lab.rgb_with_white(*white), rgb, 0.000_000_001);
+        }
+        assert_eq!(
+            Lab::<f64>::from_xyz_with_white(&XYZ::from(RGB::CYAN), Illuminant::D65),
+            Lab::from(RGB::CYAN)
+        );
+        // Print viewed under D50: the Bradford transform maps the screen white to the paper
+        // white so neutrals remain neutral while other colours shift
+        let lab_d50 = Lab::<f64>::from_rgb_with_white(&RGB::YELLOW, Illuminant::D50);
+        let lab_d65 = Lab::<f64>::from(RGB::YELLOW);
+        assert!((lab_d50.b() - lab_d65.b()).abs() > 0.1);
+        assert_approx_eq!(lab_d50.l(), lab_d65.l(), 0.5);
+    }
+}
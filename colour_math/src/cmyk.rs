@@ -0,0 +1,261 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! A rough (uncalibrated) CMYK breakdown of colours using the ideal ink model where
+//! each ink absorbs exactly one RGB primary.
+
+use num_traits_plus::float_plus::*;
+
+use crate::{clamped, ColourComponent, RGB};
+
+/// Proportions of cyan, magenta, yellow and black (key) ink coverage.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct CMYK<F: ColourComponent> {
+    pub(crate) cyan: F,
+    pub(crate) magenta: F,
+    pub(crate) yellow: F,
+    pub(crate) black: F,
+}
+
+impl<F: ColourComponent> CMYK<F> {
+    pub fn cyan(&self) -> F {
+        self.cyan
+    }
+
+    pub fn magenta(&self) -> F {
+        self.magenta
+    }
+
+    pub fn yellow(&self) -> F {
+        self.yellow
+    }
+
+    pub fn black(&self) -> F {
+        self.black
+    }
+
+    /// The sum of the ink coverages (in the range 0.0 to 4.0).
+    pub fn total_ink(&self) -> F {
+        self.cyan + self.magenta + self.yellow + self.black
+    }
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for CMYK<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.cyan.approx_eq(&other.cyan, max_diff)
+            && self.magenta.approx_eq(&other.magenta, max_diff)
+            && self.yellow.approx_eq(&other.yellow, max_diff)
+            && self.black.approx_eq(&other.black, max_diff)
+    }
+}
+
+/// Create from an array of the form [cyan, magenta, yellow, black].
+impl<F: ColourComponent> From<[F; 4]> for CMYK<F> {
+    fn from(array: [F; 4]) -> Self {
+        debug_assert!(array.iter().all(|x| (*x).is_proportion()), "{:?}", array);
+        Self {
+            cyan: array[0],
+            magenta: array[1],
+            yellow: array[2],
+            black: array[3],
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&CMYK<F>> for [F; 4] {
+    fn from(cmyk: &CMYK<F>) -> Self {
+        [cmyk.cyan, cmyk.magenta, cmyk.yellow, cmyk.black]
+    }
+}
+
+/// Separation of RGB colours into CMYK with configurable black generation,
+/// under colour removal and total ink limit.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct CMYKConverter<F: ColourComponent> {
+    black_generation: F,
+    under_colour_removal: F,
+    total_ink_limit: F,
+}
+
+impl<F: ColourComponent> Default for CMYKConverter<F> {
+    /// Full black generation and under colour removal with no ink limit.
+    fn default() -> Self {
+        Self {
+            black_generation: F::ONE,
+            under_colour_removal: F::ONE,
+            total_ink_limit: F::FOUR,
+        }
+    }
+}
+
+impl<F: ColourComponent> CMYKConverter<F> {
+    /// The proportion (0.0 to 1.0) of the grey component of a colour to print with
+    /// black ink.
+    pub fn with_black_generation(mut self, black_generation: F) -> Self {
+        debug_assert!(black_generation.is_proportion());
+        self.black_generation = black_generation;
+        self
+    }
+
+    /// The proportion (0.0 to 1.0) of the generated black to remove from the cyan,
+    /// magenta and yellow inks.  Anything less than full removal makes colours
+    /// containing black darker when printed.
+    pub fn with_under_colour_removal(mut self, under_colour_removal: F) -> Self {
+        debug_assert!(under_colour_removal.is_proportion());
+        self.under_colour_removal = under_colour_removal;
+        self
+    }
+
+    /// The maximum total ink coverage (e.g. 3.0 for 300%).  The cyan, magenta and
+    /// yellow inks are reduced (in proportion) to meet it.
+    pub fn with_total_ink_limit(mut self, total_ink_limit: F) -> Self {
+        debug_assert!(total_ink_limit >= F::ZERO && total_ink_limit <= F::FOUR);
+        self.total_ink_limit = total_ink_limit;
+        self
+    }
+
+    pub fn black_generation(&self) -> F {
+        self.black_generation
+    }
+
+    pub fn under_colour_removal(&self) -> F {
+        self.under_colour_removal
+    }
+
+    pub fn total_ink_limit(&self) -> F {
+        self.total_ink_limit
+    }
+
+    pub fn cmyk(&self, rgb: &RGB<F>) -> CMYK<F> {
+        let [cyan, magenta, yellow] = [
+            F::ONE - clamped(rgb.0[0]),
+            F::ONE - clamped(rgb.0[1]),
+            F::ONE - clamped(rgb.0[2]),
+        ];
+        let grey = cyan.min(magenta).min(yellow);
+        let black = (grey * self.black_generation).min(self.total_ink_limit);
+        let removed = black * self.under_colour_removal;
+        let remove = |ink: F| {
+            if removed < F::ONE {
+                clamped((ink - removed) / (F::ONE - removed))
+            } else {
+                F::ZERO
+            }
+        };
+        let mut cmy = [remove(cyan), remove(magenta), remove(yellow)];
+        let cmy_total = cmy[0] + cmy[1] + cmy[2];
+        let cmy_limit = self.total_ink_limit - black;
+        if cmy_total > cmy_limit {
+            let factor = cmy_limit / cmy_total;
+            for ink in cmy.iter_mut() {
+                *ink *= factor;
+            }
+        }
+        CMYK {
+            cyan: cmy[0],
+            magenta: cmy[1],
+            yellow: cmy[2],
+            black,
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for CMYK<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        CMYKConverter::default().cmyk(rgb)
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for CMYK<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&CMYK<F>> for RGB<F> {
+    fn from(cmyk: &CMYK<F>) -> Self {
+        let white = F::ONE - clamped(cmyk.black);
+        RGB::from([
+            (F::ONE - clamped(cmyk.cyan)) * white,
+            (F::ONE - clamped(cmyk.magenta)) * white,
+            (F::ONE - clamped(cmyk.yellow)) * white,
+        ])
+    }
+}
+
+impl<F: ColourComponent> From<CMYK<F>> for RGB<F> {
+    fn from(cmyk: CMYK<F>) -> Self {
+        (&cmyk).into()
+    }
+}
+
+impl_colour_interface_via_rgb!(CMYK);
+
+#[cfg(test)]
+mod cmyk_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants};
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn default_conversions() {
+        assert_eq!(
+            CMYK::from(RGB::<f64>::WHITE),
+            CMYK::from([0.0, 0.0, 0.0, 0.0])
+        );
+        assert_eq!(
+            CMYK::from(RGB::<f64>::BLACK),
+            CMYK::from([0.0, 0.0, 0.0, 1.0])
+        );
+        assert_eq!(
+            CMYK::from(RGB::<f64>::RED),
+            CMYK::from([0.0, 1.0, 1.0, 0.0])
+        );
+        assert_approx_eq!(
+            CMYK::from(RGB::<f64>::from([0.5, 0.25, 0.5])),
+            CMYK::from([0.0, 0.5, 0.0, 0.5])
+        );
+        assert_approx_eq!(CMYK::<f64>::from([0.0, 1.0, 1.0, 0.0]).rgb(), RGB::RED);
+        let values = [0.0, 0.01, 0.2, 0.5, 0.8, 0.99, 1.0];
+        for red in values.iter() {
+            for green in values.iter() {
+                for blue in values.iter() {
+                    let rgb = RGB::<f64>::from([*red, *green, *blue]);
+                    assert_approx_eq!(RGB::from(CMYK::from(rgb)), rgb, 0.000_000_001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn black_generation_and_removal() {
+        let rgb = RGB::<f64>::from([0.6, 0.4, 0.2]);
+        let no_black = CMYKConverter::default().with_black_generation(0.0);
+        assert_approx_eq!(no_black.cmyk(&rgb), CMYK::from([0.4, 0.6, 0.8, 0.0]));
+        assert_approx_eq!(RGB::from(no_black.cmyk(&rgb)), rgb, 0.000_000_001);
+        let half_black = CMYKConverter::default().with_black_generation(0.5);
+        let cmyk = half_black.cmyk(&rgb);
+        assert_approx_eq!(cmyk.black(), 0.2);
+        assert_approx_eq!(RGB::from(cmyk), rgb, 0.000_000_001);
+        let no_ucr = CMYKConverter::default().with_under_colour_removal(0.0);
+        let cmyk = no_ucr.cmyk(&rgb);
+        assert_approx_eq!(cmyk, CMYK::from([0.4, 0.6, 0.8, 0.4]));
+        assert!(RGB::from(cmyk).value() < rgb.value());
+    }
+
+    #[test]
+    fn total_ink_limit() {
+        let rich_black = CMYKConverter::default().with_under_colour_removal(0.0);
+        assert_approx_eq!(rich_black.cmyk(&RGB::BLACK).total_ink(), 4.0);
+        let limited = rich_black.with_total_ink_limit(3.0);
+        let cmyk = limited.cmyk(&RGB::BLACK);
+        assert_approx_eq!(cmyk.total_ink(), 3.0);
+        assert_approx_eq!(cmyk, CMYK::from([2.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0, 1.0]));
+        let tight = CMYKConverter::default()
+            .with_black_generation(0.0)
+            .with_total_ink_limit(1.5);
+        let cmyk = tight.cmyk(&RGB::<f64>::from([0.1, 0.1, 0.4]));
+        assert_approx_eq!(cmyk.total_ink(), 1.5);
+        assert_approx_eq!(cmyk.cyan(), cmyk.magenta());
+        assert!(cmyk.yellow() < cmyk.cyan());
+    }
+}
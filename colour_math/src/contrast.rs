@@ -0,0 +1,164 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{cie::SRGB_TO_XYZ, f, linear_rgb::LinearRGB, ColourComponent, CCI, RGB};
+
+/// Relative luminance (as defined by WCAG 2.x) in the range 0.0 (black) to 1.0 (white).
+pub fn relative_luminance<F: ColourComponent>(rgb: &RGB<F>) -> F {
+    let linear_rgb = LinearRGB::from(rgb);
+    let luminance = SRGB_TO_XYZ[1]
+        .iter()
+        .zip(linear_rgb.iter())
+        .fold(F::ZERO, |acc, (k, c)| acc + f::<F>(*k) * *c);
+    luminance.min(F::ONE)
+}
+
+/// The WCAG 2.x contrast ratio of the two colours.  The result is in the range 1.0 to
+/// 21.0 and does not depend on the order of the arguments.  WCAG level AA requires a
+/// ratio of at least 4.5 for normal text and 3.0 for large text.
+pub fn wcag_contrast_ratio<F: ColourComponent>(rgb_1: &RGB<F>, rgb_2: &RGB<F>) -> F {
+    let luminance_1 = relative_luminance(rgb_1);
+    let luminance_2 = relative_luminance(rgb_2);
+    let (lighter, darker) = if luminance_1 > luminance_2 {
+        (luminance_1, luminance_2)
+    } else {
+        (luminance_2, luminance_1)
+    };
+    (lighter + f(0.05)) / (darker + f(0.05))
+}
+
+// Screen luminance estimate used by APCA (simple power curve rather than the sRGB
+// transfer function)
+fn apca_luminance<F: ColourComponent>(rgb: &RGB<F>) -> F {
+    let exponent = f::<F>(2.4);
+    let luminance = f::<F>(0.212_672_9) * rgb[CCI::Red].powf(exponent)
+        + f::<F>(0.715_152_2) * rgb[CCI::Green].powf(exponent)
+        + f::<F>(0.072_175_0) * rgb[CCI::Blue].powf(exponent);
+    let black_threshold = f::<F>(0.022);
+    if luminance < black_threshold {
+        luminance + (black_threshold - luminance).powf(f(1.414))
+    } else {
+        luminance
+    }
+}
+
+/// The APCA (SAPC 0.0.98G) lightness contrast (Lc) of `text` on `background`.
+/// Positive values are for dark text on a light background and negative values for
+/// light text on a dark background.  The magnitude ranges from 0.0 to about 108.0 with
+/// 60.0 being the suggested minimum for body text.
+pub fn apca_contrast<F: ColourComponent>(text: &RGB<F>, background: &RGB<F>) -> F {
+    let text_luminance = apca_luminance(text);
+    let background_luminance = apca_luminance(background);
+    if (background_luminance - text_luminance).abs() < f(0.000_5) {
+        return F::ZERO;
+    }
+    let scale = f::<F>(1.14);
+    let low_clip = f::<F>(0.1);
+    let offset = f::<F>(0.027);
+    let contrast = if background_luminance > text_luminance {
+        let sapc = (background_luminance.powf(f(0.56)) - text_luminance.powf(f(0.57))) * scale;
+        if sapc < low_clip {
+            F::ZERO
+        } else {
+            sapc - offset
+        }
+    } else {
+        let sapc = (background_luminance.powf(f(0.65)) - text_luminance.powf(f(0.62))) * scale;
+        if sapc > -low_clip {
+            F::ZERO
+        } else {
+            sapc + offset
+        }
+    };
+    contrast * f(100.0)
+}
+
+/// The candidate with the highest WCAG contrast ratio against `background`.  The first
+/// candidate wins ties.  Returns `None` if there are no candidates.
+pub fn best_foreground_rgb_from<F: ColourComponent>(
+    background: &RGB<F>,
+    candidates: &[RGB<F>],
+) -> Option<RGB<F>> {
+    let mut best: Option<(RGB<F>, F)> = None;
+    for candidate in candidates.iter() {
+        let ratio = wcag_contrast_ratio(background, candidate);
+        match best {
+            Some((_, best_ratio)) if best_ratio >= ratio => (),
+            _ => best = Some((*candidate, ratio)),
+        }
+    }
+    best.map(|(rgb, _)| rgb)
+}
+
+#[cfg(test)]
+mod contrast_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn luminance() {
+        assert_eq!(relative_luminance(&RGB::<f64>::BLACK), 0.0);
+        assert_approx_eq!(relative_luminance(&RGB::<f64>::WHITE), 1.0);
+        assert_approx_eq!(relative_luminance(&RGB::<f64>::RED), 0.2126, 0.000_1);
+        assert_approx_eq!(relative_luminance(&RGB::<f64>::GREEN), 0.7152, 0.000_1);
+        assert_approx_eq!(relative_luminance(&RGB::<f64>::BLUE), 0.0722, 0.000_1);
+    }
+
+    #[test]
+    fn wcag() {
+        assert_approx_eq!(wcag_contrast_ratio(&RGB::<f64>::BLACK, &RGB::WHITE), 21.0);
+        assert_approx_eq!(wcag_contrast_ratio(&RGB::<f64>::WHITE, &RGB::BLACK), 21.0);
+        assert_approx_eq!(wcag_contrast_ratio(&RGB::<f64>::RED, &RGB::RED), 1.0);
+        // #777777 on white is a well known near miss for AA
+        let grey = RGB::<f64>::from([0x77 as f64 / 255.0; 3]);
+        assert_approx_eq!(wcag_contrast_ratio(&grey, &RGB::WHITE), 4.48, 0.001);
+    }
+
+    #[test]
+    fn apca() {
+        assert_approx_eq!(
+            apca_contrast(&RGB::<f64>::BLACK, &RGB::WHITE),
+            106.04,
+            0.000_1
+        );
+        assert_approx_eq!(
+            apca_contrast(&RGB::<f64>::WHITE, &RGB::BLACK),
+            -107.88,
+            0.000_1
+        );
+        let grey = RGB::<f64>::from([0x88 as f64 / 255.0; 3]);
+        assert_approx_eq!(apca_contrast(&grey, &RGB::WHITE), 63.06, 0.000_1);
+        assert_eq!(apca_contrast(&grey, &grey), 0.0);
+    }
+
+    #[test]
+    fn best_foreground() {
+        assert_eq!(best_foreground_rgb_from::<f64>(&RGB::WHITE, &[]), None);
+        let candidates = [RGB::<f64>::YELLOW, RGB::BLUE, RGB::WHITE];
+        assert_eq!(
+            RGB::<f64>::WHITE.best_foreground_rgb_from(&candidates),
+            RGB::BLUE
+        );
+        assert_eq!(
+            RGB::<f64>::BLACK.best_foreground_rgb_from(&candidates),
+            RGB::WHITE
+        );
+        for rgb in RGB::<f64>::PRIMARIES
+            .iter()
+            .chain(RGB::SECONDARIES.iter())
+            .chain(RGB::GREYS.iter())
+        {
+            let best = rgb.best_foreground_rgb();
+            let other = if best == RGB::BLACK {
+                RGB::WHITE
+            } else {
+                RGB::BLACK
+            };
+            assert!(rgb.wcag_contrast_ratio(&best) >= rgb.wcag_contrast_ratio(&other));
+        }
+        // The old luma threshold chose white text for these backgrounds
+        assert_eq!(RGB::<f64>::RED.best_foreground_rgb(), RGB::BLACK);
+        let grey = RGB::<f64>::from([0.5, 0.5, 0.5]);
+        assert_eq!(grey.best_foreground_rgb(), RGB::BLACK);
+    }
+}
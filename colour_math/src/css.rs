@@ -0,0 +1,443 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use std::str::FromStr;
+
+use crate::{
+    clamped, f, hsl::HSL, hsv::HSV, named_colours, urgb::URGB, urgba::URGBA, ColourComponent, RGB,
+    RGBA,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CSSError {
+    MalformedText(String),
+}
+
+impl std::fmt::Display for CSSError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CSSError::MalformedText(string) => write!(f, "Malformed CSS colour: {}", string),
+        }
+    }
+}
+
+impl std::error::Error for CSSError {}
+
+/// The CSS notations available for serialising colours.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CSSFormat {
+    /// `#rrggbb` (or `#rrggbbaa` if not opaque)
+    Hex,
+    /// `rgb(r g b)` with components in the range 0 to 255
+    RGB,
+    /// `hsl(h s% l%)`
+    HSL,
+    /// `hwb(h w% b%)`
+    HWB,
+}
+
+impl Default for CSSFormat {
+    fn default() -> Self {
+        CSSFormat::Hex
+    }
+}
+
+fn parse_hex<F: ColourComponent>(digits: &str) -> Option<[F; 4]> {
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let values: Vec<u8> = match digits.len() {
+        3 | 4 => digits
+            .chars()
+            .map(|c| c.to_digit(16).unwrap() as u8 * 17)
+            .collect(),
+        6 | 8 => (0..digits.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+            .collect(),
+        _ => return None,
+    };
+    let mut array = [F::ONE; 4];
+    for (component, value) in array.iter_mut().zip(values.iter()) {
+        *component = f::<F>(*value as f64) / f(255.0);
+    }
+    Some(array)
+}
+
+fn parse_number<F: ColourComponent>(token: &str) -> Option<F> {
+    let value = f64::from_str(token).ok()?;
+    if value.is_finite() {
+        Some(f(value))
+    } else {
+        None
+    }
+}
+
+// A number (scaled so that `full_scale` is 1.0) or a percentage
+fn parse_proportion<F: ColourComponent>(token: &str, full_scale: f64) -> Option<F> {
+    if let Some(percentage) = token.strip_suffix('%') {
+        Some(clamped(parse_number::<F>(percentage)? / f(100.0)))
+    } else {
+        Some(clamped(parse_number::<F>(token)? / f(full_scale)))
+    }
+}
+
+fn parse_percentage<F: ColourComponent>(token: &str) -> Option<F> {
+    Some(clamped(
+        parse_number::<F>(token.strip_suffix('%')?)? / f(100.0),
+    ))
+}
+
+fn parse_hue<F: ColourComponent>(token: &str) -> Option<F> {
+    let (number, degrees_per_unit) = if let Some(number) = token.strip_suffix("deg") {
+        (number, 1.0)
+    } else if let Some(number) = token.strip_suffix("grad") {
+        (number, 0.9)
+    } else if let Some(number) = token.strip_suffix("rad") {
+        (number, 180.0 / std::f64::consts::PI)
+    } else if let Some(number) = token.strip_suffix("turn") {
+        (number, 360.0)
+    } else {
+        (token, 1.0)
+    };
+    let degrees = parse_number::<F>(number)? * f(degrees_per_unit);
+    Some(degrees % F::DEG_360)
+}
+
+// The arguments of a CSS colour function in either the legacy (comma separated) or
+// modern (space separated with "/" before alpha) syntax.
+fn function_arguments(arguments: &str) -> Option<(Vec<&str>, Option<&str>)> {
+    let mut tokens: Vec<&str> = if arguments.contains(',') {
+        arguments.split(',').map(|t| t.trim()).collect()
+    } else {
+        let (colour, alpha) = match arguments.find('/') {
+            Some(index) => (&arguments[..index], Some(arguments[index + 1..].trim())),
+            None => (arguments, None),
+        };
+        let mut tokens: Vec<&str> = colour.split_whitespace().collect();
+        if let Some(alpha) = alpha {
+            tokens.push(alpha);
+        }
+        tokens
+    };
+    if tokens.iter().any(|t| t.is_empty()) {
+        return None;
+    }
+    match tokens.len() {
+        3 => Some((tokens, None)),
+        4 => {
+            let alpha = tokens.pop();
+            Some((tokens, alpha))
+        }
+        _ => None,
+    }
+}
+
+fn parse_function<F: ColourComponent>(name: &str, arguments: &str) -> Option<[F; 4]> {
+    let (tokens, alpha) = function_arguments(arguments)?;
+    let alpha = match alpha {
+        Some(token) => parse_proportion(token, 1.0)?,
+        None => F::ONE,
+    };
+    let rgb: RGB<F> = match name {
+        "rgb" | "rgba" => [
+            parse_proportion(tokens[0], 255.0)?,
+            parse_proportion(tokens[1], 255.0)?,
+            parse_proportion(tokens[2], 255.0)?,
+        ]
+        .into(),
+        "hsl" | "hsla" => HSL::from([
+            parse_hue(tokens[0])?,
+            parse_percentage(tokens[1])?,
+            parse_percentage(tokens[2])?,
+        ])
+        .into(),
+        "hwb" => {
+            let hue = parse_hue::<F>(tokens[0])?;
+            let whiteness = parse_percentage::<F>(tokens[1])?;
+            let blackness = parse_percentage::<F>(tokens[2])?;
+            if whiteness + blackness >= F::ONE {
+                let grey = whiteness / (whiteness + blackness);
+                [grey, grey, grey].into()
+            } else {
+                let value = F::ONE - blackness;
+                HSV::from([hue, F::ONE - whiteness / value, value]).into()
+            }
+        }
+        _ => return None,
+    };
+    Some([rgb.0[0], rgb.0[1], rgb.0[2], alpha])
+}
+
+/// Parse any CSS Color Level 4 sRGB notation (hex, `rgb()`, `rgba()`, `hsl()`,
+/// `hsla()`, `hwb()`, a named colour or `transparent`) into red, green, blue and alpha
+/// components in the range 0.0 to 1.0.
+pub fn parse_css_colour<F: ColourComponent>(string: &str) -> Result<[F; 4], CSSError> {
+    let text = string.trim().to_lowercase();
+    let result = if let Some(digits) = text.strip_prefix('#') {
+        parse_hex(digits)
+    } else if let Some(index) = text.find('(') {
+        match text.strip_suffix(')') {
+            Some(text) => parse_function(text[..index].trim(), &text[index + 1..]),
+            None => None,
+        }
+    } else if text == "transparent" {
+        Some([F::ZERO; 4])
+    } else {
+        named_colours::css_rgb8_for_name(&text).map(|rgb8| {
+            let rgb = RGB::<F>::from(rgb8);
+            [rgb.0[0], rgb.0[1], rgb.0[2], F::ONE]
+        })
+    };
+    result.ok_or_else(|| CSSError::MalformedText(string.to_string()))
+}
+
+// Up to `decimals` decimal places without trailing zeros
+fn css_number<F: ColourComponent>(value: F, decimals: usize) -> String {
+    let string = format!("{:.*}", decimals, value.to_f64().unwrap());
+    let string = if string.contains('.') {
+        string.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &string
+    };
+    if string == "-0" {
+        "0".to_string()
+    } else {
+        string.to_string()
+    }
+}
+
+/// The CSS representation of the colour (with the alpha component omitted if it is 1.0).
+pub fn css_string<F: ColourComponent>(rgb: &RGB<F>, alpha: F, format: CSSFormat) -> String {
+    let opaque = alpha >= F::ONE;
+    let alpha_suffix = if opaque {
+        String::new()
+    } else {
+        format!(" / {}", css_number(alpha, 3))
+    };
+    match format {
+        CSSFormat::Hex => {
+            let urgb = URGB::<u8>::from(rgb);
+            let mut string = format!("#{:02x}{:02x}{:02x}", urgb[0], urgb[1], urgb[2]);
+            if !opaque {
+                let alpha = (alpha * f(255.0)).round().to_u8().unwrap();
+                string += &format!("{:02x}", alpha);
+            }
+            string
+        }
+        CSSFormat::RGB => {
+            let urgb = URGB::<u8>::from(rgb);
+            format!("rgb({} {} {}{})", urgb[0], urgb[1], urgb[2], alpha_suffix)
+        }
+        CSSFormat::HSL => {
+            let [hue, saturation, lightness] = <[F; 3]>::from(&HSL::from(rgb));
+            format!(
+                "hsl({} {}% {}%{})",
+                css_number(hue, 2),
+                css_number(saturation * f(100.0), 2),
+                css_number(lightness * f(100.0), 2),
+                alpha_suffix
+            )
+        }
+        CSSFormat::HWB => {
+            let [hue, saturation, value] = <[F; 3]>::from(&HSV::from(rgb));
+            format!(
+                "hwb({} {}% {}%{})",
+                css_number(hue, 2),
+                css_number((F::ONE - saturation) * value * f(100.0), 2),
+                css_number((F::ONE - value) * f(100.0), 2),
+                alpha_suffix
+            )
+        }
+    }
+}
+
+/// Conversion to and from CSS colour strings.
+pub trait CSSColour: Sized {
+    /// Parse a CSS colour.  Types without an alpha component ignore any supplied alpha.
+    fn from_css_str(string: &str) -> Result<Self, CSSError>;
+    fn css_string(&self, format: CSSFormat) -> String;
+}
+
+impl<F: ColourComponent> CSSColour for RGB<F> {
+    fn from_css_str(string: &str) -> Result<Self, CSSError> {
+        let array = parse_css_colour::<F>(string)?;
+        Ok([array[0], array[1], array[2]].into())
+    }
+
+    fn css_string(&self, format: CSSFormat) -> String {
+        css_string(self, F::ONE, format)
+    }
+}
+
+impl<F: ColourComponent> CSSColour for RGBA<F> {
+    fn from_css_str(string: &str) -> Result<Self, CSSError> {
+        Ok(parse_css_colour::<F>(string)?.into())
+    }
+
+    fn css_string(&self, format: CSSFormat) -> String {
+        let array = <[F; 4]>::from(self);
+        css_string(&RGB::from([array[0], array[1], array[2]]), array[3], format)
+    }
+}
+
+impl CSSColour for URGB<u8> {
+    fn from_css_str(string: &str) -> Result<Self, CSSError> {
+        Ok(RGB::<f64>::from_css_str(string)?.into())
+    }
+
+    fn css_string(&self, format: CSSFormat) -> String {
+        RGB::<f64>::from(self).css_string(format)
+    }
+}
+
+impl CSSColour for URGBA<u8> {
+    fn from_css_str(string: &str) -> Result<Self, CSSError> {
+        Ok(RGBA::<f64>::from_css_str(string)?.into())
+    }
+
+    fn css_string(&self, format: CSSFormat) -> String {
+        RGBA::<f64>::from(self).css_string(format)
+    }
+}
+
+impl<F: ColourComponent> FromStr for RGB<F> {
+    type Err = CSSError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::from_css_str(string)
+    }
+}
+
+impl<F: ColourComponent> FromStr for RGBA<F> {
+    type Err = CSSError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::from_css_str(string)
+    }
+}
+
+#[cfg(test)]
+mod css_tests {
+    use super::*;
+    use crate::{HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn hex_notations() {
+        assert_eq!(RGB::<f64>::from_str("#f00"), Ok(RGB::RED));
+        assert_eq!(RGB::<f64>::from_str("#FF0000"), Ok(RGB::RED));
+        assert_eq!(
+            URGBA::<u8>::from_css_str("#f008"),
+            Ok(URGBA::from([0xFF, 0, 0, 0x88]))
+        );
+        assert_eq!(
+            URGBA::<u8>::from_css_str("#12345678"),
+            Ok(URGBA::from([0x12, 0x34, 0x56, 0x78]))
+        );
+        assert!(RGB::<f64>::from_str("#12345").is_err());
+        assert!(RGB::<f64>::from_str("#ggg").is_err());
+    }
+
+    #[test]
+    fn functional_notations() {
+        let expected = URGB::<u8>::from([255, 128, 0]);
+        for string in [
+            "rgb(255, 128, 0)",
+            "rgb(255 128 0)",
+            "RGB(100% 50.2% 0%)",
+            "rgba(255, 128, 0, 1)",
+            "rgb(255 128 0 / 100%)",
+            "hsl(30.12 100% 50%)",
+            "hsl(30.12deg, 100%, 50%)",
+            "hsla(0.08367turn 100% 50% / 1)",
+            "hwb(30.12 0% 0%)",
+        ]
+        .iter()
+        {
+            assert_eq!(URGB::<u8>::from_css_str(string), Ok(expected), "{}", string);
+        }
+        let rgba = RGBA::<f64>::from_str("rgb(0 0 255 / 0.25)").unwrap();
+        assert_approx_eq!(rgba, RGBA::from([0.0, 0.0, 1.0, 0.25]));
+        let rgba = RGBA::<f64>::from_str("hsla(240, 100%, 50%, 25%)").unwrap();
+        assert_approx_eq!(rgba, RGBA::from([0.0, 0.0, 1.0, 0.25]));
+        assert_approx_eq!(
+            RGB::<f64>::from_str("hwb(0 60% 60%)").unwrap(),
+            RGB::from([0.5, 0.5, 0.5])
+        );
+        assert_approx_eq!(
+            RGB::<f64>::from_str("hwb(120 20% 20%)").unwrap(),
+            RGB::from([0.2, 0.8, 0.2])
+        );
+        for string in ["rgb(1, 2)", "rgb(1 2 3", "lab(50 0 0)", "hsl(0 1 1)"].iter() {
+            assert!(RGB::<f64>::from_str(string).is_err(), "{}", string);
+        }
+    }
+
+    #[test]
+    fn named_notations() {
+        assert_eq!(
+            URGB::<u8>::from_css_str("CornflowerBlue"),
+            Ok(URGB::from([0x64, 0x95, 0xED]))
+        );
+        assert_eq!(
+            RGBA::<f64>::from_str("transparent"),
+            Ok(RGBA::from([0.0; 4]))
+        );
+        assert!(RGB::<f64>::from_str("blurple").is_err());
+        // X11-only names and loosely spelt CSS ones aren't CSS colours
+        assert!(named_colours::rgb8_for_name("navyblue").is_some());
+        assert!(RGB::<f64>::from_css_str("navyblue").is_err());
+        assert!(RGB::<f64>::from_css_str("light gray").is_err());
+        assert!(RGB::<f64>::from_css_str("light_gray").is_err());
+        assert!(RGB::<f64>::from_css_str("LightGray").is_ok());
+        // existing URGB notations still work and CSS ones are now accepted
+        assert_eq!(
+            URGB::<u8>::from_str("RGB8(0xFF, 0x00, 0x00)"),
+            Ok(URGB::from([255, 0, 0]))
+        );
+        assert_eq!(
+            URGB::<u8>::from_str("rgb(255 0 0)"),
+            Ok(URGB::from([255, 0, 0]))
+        );
+        assert_eq!(
+            URGBA::<u8>::from_str("#f00"),
+            Ok(URGBA::from([255, 0, 0, 255]))
+        );
+    }
+
+    #[test]
+    fn serialisation() {
+        let rgb = RGB::<f64>::from(URGB::<u8>::from([0x66, 0x33, 0x99]));
+        assert_eq!(rgb.css_string(CSSFormat::Hex), "#663399");
+        assert_eq!(rgb.css_string(CSSFormat::RGB), "rgb(102 51 153)");
+        assert_eq!(rgb.css_string(CSSFormat::HSL), "hsl(270 50% 40%)");
+        assert_eq!(rgb.css_string(CSSFormat::HWB), "hwb(270 20% 40%)");
+        let rgba = RGBA::<f64>::from([1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(rgba.css_string(CSSFormat::Hex), "#ff000080");
+        assert_eq!(rgba.css_string(CSSFormat::RGB), "rgb(255 0 0 / 0.5)");
+        assert_eq!(rgba.css_string(CSSFormat::HSL), "hsl(0 100% 50% / 0.5)");
+        assert_eq!(
+            RGB::<f64>::WHITE.css_string(CSSFormat::HSL),
+            "hsl(0 0% 100%)"
+        );
+        for format in [
+            CSSFormat::Hex,
+            CSSFormat::RGB,
+            CSSFormat::HSL,
+            CSSFormat::HWB,
+        ]
+        .iter()
+        {
+            for urgb in [
+                URGB::<u8>::from([0x64, 0x95, 0xED]),
+                URGB::from([0, 0, 0]),
+                URGB::from([0x12, 0xFE, 0x80]),
+            ]
+            .iter()
+            {
+                let string = urgb.css_string(*format);
+                assert_eq!(URGB::<u8>::from_css_str(&string), Ok(*urgb), "{}", string);
+            }
+        }
+    }
+}
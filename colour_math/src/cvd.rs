@@ -0,0 +1,183 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{
+    cie::apply_matrix, clamped, image::Transformer, linear_rgb::LinearRGB, ColourComponent, RGB,
+};
+
+/// The types of dichromatic colour vision deficiency that can be simulated.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColourVisionDeficiency {
+    /// Missing/anomalous long wavelength (red) cones
+    Protan,
+    /// Missing/anomalous medium wavelength (green) cones
+    Deutan,
+    /// Missing/anomalous short wavelength (blue) cones
+    Tritan,
+}
+
+impl ColourVisionDeficiency {
+    pub const ALL: [ColourVisionDeficiency; 3] = [
+        ColourVisionDeficiency::Protan,
+        ColourVisionDeficiency::Deutan,
+        ColourVisionDeficiency::Tritan,
+    ];
+
+    // Machado, Oliveira and Fernandes (2009) matrices (for linear RGB) for full severity
+    // i.e. protanopia, deuteranopia and tritanopia.
+    fn dichromat_matrix(&self) -> &'static [[f64; 3]; 3] {
+        match self {
+            ColourVisionDeficiency::Protan => &[
+                [0.152_286, 1.052_583, -0.204_868],
+                [0.114_503, 0.786_281, 0.099_216],
+                [-0.003_882, -0.048_116, 1.051_998],
+            ],
+            ColourVisionDeficiency::Deutan => &[
+                [0.367_322, 0.860_646, -0.227_968],
+                [0.280_085, 0.672_501, 0.047_413],
+                [-0.011_820, 0.042_940, 0.968_881],
+            ],
+            ColourVisionDeficiency::Tritan => &[
+                [1.255_528, -0.076_749, -0.178_779],
+                [-0.078_411, 0.930_809, 0.147_602],
+                [0.004_733, 0.691_367, 0.303_900],
+            ],
+        }
+    }
+
+    /// The matrix (for linear RGB) simulating this deficiency at the given severity
+    /// (0.0 for normal vision to 1.0 for dichromacy).  Anomalous trichromacy (partial
+    /// severity) is approximated by interpolating between the identity matrix and the
+    /// dichromat matrix.
+    pub fn matrix(&self, severity: f64) -> [[f64; 3]; 3] {
+        debug_assert!((0.0..=1.0).contains(&severity), "severity: {}", severity);
+        let dichromat_matrix = self.dichromat_matrix();
+        let mut matrix = [[0.0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let identity = if i == j { 1.0 } else { 0.0 };
+                *cell = identity + severity * (dichromat_matrix[i][j] - identity);
+            }
+        }
+        matrix
+    }
+}
+
+/// Simulates how colours appear to somebody with a colour vision deficiency.  Can be
+/// used directly on colours or to transform images via `ImageIfce::transformed()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CVDSimulator<F: ColourComponent> {
+    deficiency: ColourVisionDeficiency,
+    severity: F,
+    matrix: [[f64; 3]; 3],
+}
+
+impl<F: ColourComponent> CVDSimulator<F> {
+    pub fn new(deficiency: ColourVisionDeficiency, severity: F) -> Self {
+        debug_assert!(severity.is_proportion(), "severity: {:?}", severity);
+        Self {
+            deficiency,
+            severity,
+            matrix: deficiency.matrix(severity.to_f64().unwrap()),
+        }
+    }
+
+    pub fn deficiency(&self) -> ColourVisionDeficiency {
+        self.deficiency
+    }
+
+    pub fn severity(&self) -> F {
+        self.severity
+    }
+
+    pub fn simulate(&self, rgb: &RGB<F>) -> RGB<F> {
+        let linear_rgb = LinearRGB::from(rgb);
+        let mut array = apply_matrix(&self.matrix, linear_rgb.0);
+        for component in array.iter_mut() {
+            *component = clamped(*component);
+        }
+        LinearRGB::from(array).into()
+    }
+}
+
+impl<F: ColourComponent> Transformer<RGB<F>> for CVDSimulator<F> {
+    fn transform(&self, pixel: &RGB<F>) -> RGB<F> {
+        self.simulate(pixel)
+    }
+}
+
+#[cfg(test)]
+mod cvd_tests {
+    use super::*;
+    use crate::{
+        difference::DifferenceMetric,
+        image::{GenericImage, ImageIfce, OpaqueImage},
+        ColourInterface, HueConstants, RGBConstants,
+    };
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn zero_severity_is_normal_vision() {
+        for deficiency in ColourVisionDeficiency::ALL.iter() {
+            let simulator = CVDSimulator::<f64>::new(*deficiency, 0.0);
+            for rgb in RGB::<f64>::PRIMARIES.iter().chain(RGB::SECONDARIES.iter()) {
+                assert_approx_eq!(simulator.simulate(rgb), *rgb, 0.000_000_001);
+            }
+        }
+    }
+
+    #[test]
+    fn greys_are_unaffected() {
+        for deficiency in ColourVisionDeficiency::ALL.iter() {
+            for severity in [0.25, 0.5, 1.0].iter() {
+                for value in [0.0, 0.2, 0.5, 0.8, 1.0].iter() {
+                    let grey = RGB::<f64>::from([*value, *value, *value]);
+                    assert_approx_eq!(
+                        grey.cvd_simulated_rgb(*deficiency, *severity),
+                        grey,
+                        0.000_01
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn confusion_colours_converge() {
+        let metric = DifferenceMetric::CIEDE2000;
+        let normal = RGB::<f64>::RED.difference(&RGB::GREEN, metric);
+        for deficiency in [
+            ColourVisionDeficiency::Protan,
+            ColourVisionDeficiency::Deutan,
+        ]
+        .iter()
+        {
+            let mut previous = normal;
+            for severity in [0.5, 1.0].iter() {
+                let simulator = CVDSimulator::new(*deficiency, *severity);
+                let red = simulator.simulate(&RGB::RED);
+                let green = simulator.simulate(&RGB::GREEN);
+                let difference = red.difference(&green, metric);
+                assert!(difference < previous);
+                previous = difference;
+            }
+        }
+        let normal = RGB::<f64>::BLUE.difference(&RGB::GREEN, metric);
+        let simulator = CVDSimulator::new(ColourVisionDeficiency::Tritan, 1.0);
+        let blue = simulator.simulate(&RGB::BLUE);
+        let green = simulator.simulate(&RGB::GREEN);
+        assert!(blue.difference(&green, metric) < normal);
+    }
+
+    #[test]
+    fn transform_images() {
+        let pixels = vec![RGB::<f64>::RED, RGB::GREEN, RGB::BLUE, RGB::WHITE];
+        let simulator = CVDSimulator::new(ColourVisionDeficiency::Deutan, 1.0);
+        let image = OpaqueImage::from((pixels.clone(), 2)).transformed(simulator);
+        let generic_image = GenericImage::from((pixels.clone(), 2)).transformed(simulator);
+        for (i, rgb) in pixels.iter().enumerate() {
+            let expected = rgb.cvd_simulated_rgb(ColourVisionDeficiency::Deutan, 1.0);
+            assert_eq!(image[i / 2][i % 2], expected);
+            assert_eq!(generic_image[i / 2][i % 2], expected);
+        }
+    }
+}
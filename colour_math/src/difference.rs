@@ -0,0 +1,240 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{f, ColourComponent, ColourInterface, Lab};
+
+/// The available measures of the difference between two colours.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DifferenceMetric {
+    /// Euclidean distance in CIE L*a*b* space (ΔE*ab 1976).
+    CIE76,
+    /// CIE 1994 ΔE*94 using the graphic arts weightings.
+    CIE94,
+    /// CIE ΔE*00 (CIEDE2000) with unit parametric weightings.
+    CIEDE2000,
+    /// Distance in the (cylindrical) hue/chroma/value space used by `HCV`.
+    HCV,
+}
+
+impl Default for DifferenceMetric {
+    fn default() -> Self {
+        DifferenceMetric::CIEDE2000
+    }
+}
+
+/// The CIE 1976 colour difference i.e. the Euclidean distance between the two colours
+/// in L*a*b* space.
+pub fn delta_e_76<F: ColourComponent>(lab_1: &Lab<F>, lab_2: &Lab<F>) -> F {
+    let delta_l = lab_1.l - lab_2.l;
+    let delta_a = lab_1.a - lab_2.a;
+    let delta_b = lab_1.b - lab_2.b;
+    (delta_l * delta_l + delta_a * delta_a + delta_b * delta_b).sqrt()
+}
+
+/// The CIE 1994 colour difference using the graphic arts application weightings.
+/// NB: this metric is not symmetric and `lab_1` is treated as the reference colour.
+pub fn delta_e_94<F: ColourComponent>(lab_1: &Lab<F>, lab_2: &Lab<F>) -> F {
+    let c_1 = lab_1.a.hypot(lab_1.b);
+    let c_2 = lab_2.a.hypot(lab_2.b);
+    let delta_l = lab_1.l - lab_2.l;
+    let delta_c = c_1 - c_2;
+    let delta_a = lab_1.a - lab_2.a;
+    let delta_b = lab_1.b - lab_2.b;
+    // Rounding errors can make this slightly negative when the hues are the same
+    let delta_h_sqrd = (delta_a * delta_a + delta_b * delta_b - delta_c * delta_c).max(F::ZERO);
+    let s_c = F::ONE + f::<F>(0.045) * c_1;
+    let s_h = F::ONE + f::<F>(0.015) * c_1;
+    (delta_l * delta_l + (delta_c / s_c).powi(2) + delta_h_sqrd / (s_h * s_h)).sqrt()
+}
+
+/// The CIEDE2000 colour difference as described by Sharma, Wu and Dalal (2005) with
+/// the parametric weighting factors all equal to one.
+pub fn delta_e_2000<F: ColourComponent>(lab_1: &Lab<F>, lab_2: &Lab<F>) -> F {
+    let twenty_five_pow_7 = f::<F>(6_103_515_625.0);
+    let c_bar = (lab_1.a.hypot(lab_1.b) + lab_2.a.hypot(lab_2.b)) / F::TWO;
+    let c_bar_pow_7 = c_bar.powi(7);
+    let g = F::HALF * (F::ONE - (c_bar_pow_7 / (c_bar_pow_7 + twenty_five_pow_7)).sqrt());
+    let a_1 = (F::ONE + g) * lab_1.a;
+    let a_2 = (F::ONE + g) * lab_2.a;
+    let c_1 = a_1.hypot(lab_1.b);
+    let c_2 = a_2.hypot(lab_2.b);
+    let hue_degrees = |a: F, b: F| {
+        if a == F::ZERO && b == F::ZERO {
+            F::ZERO
+        } else {
+            let degrees = b.atan2(a).to_degrees();
+            if degrees < F::ZERO {
+                degrees + F::DEG_360
+            } else {
+                degrees
+            }
+        }
+    };
+    let h_1 = hue_degrees(a_1, lab_1.b);
+    let h_2 = hue_degrees(a_2, lab_2.b);
+
+    let delta_l = lab_2.l - lab_1.l;
+    let delta_c = c_2 - c_1;
+    let delta_h_degrees = if c_1 * c_2 == F::ZERO {
+        F::ZERO
+    } else if (h_2 - h_1).abs() <= F::DEG_180 {
+        h_2 - h_1
+    } else if h_2 <= h_1 {
+        h_2 - h_1 + F::DEG_360
+    } else {
+        h_2 - h_1 - F::DEG_360
+    };
+    let delta_h = F::TWO * (c_1 * c_2).sqrt() * (delta_h_degrees.to_radians() / F::TWO).sin();
+
+    let l_bar = (lab_1.l + lab_2.l) / F::TWO;
+    let c_bar = (c_1 + c_2) / F::TWO;
+    let h_bar = if c_1 * c_2 == F::ZERO {
+        h_1 + h_2
+    } else if (h_1 - h_2).abs() <= F::DEG_180 {
+        (h_1 + h_2) / F::TWO
+    } else if h_1 + h_2 < F::DEG_360 {
+        (h_1 + h_2 + F::DEG_360) / F::TWO
+    } else {
+        (h_1 + h_2 - F::DEG_360) / F::TWO
+    };
+    let t = F::ONE - f::<F>(0.17) * (h_bar - f(30.0)).to_radians().cos()
+        + f::<F>(0.24) * (F::TWO * h_bar).to_radians().cos()
+        + f::<F>(0.32) * (F::THREE * h_bar + f(6.0)).to_radians().cos()
+        - f::<F>(0.20) * (F::FOUR * h_bar - f(63.0)).to_radians().cos();
+    let delta_theta = f::<F>(30.0) * (-((h_bar - f(275.0)) / f(25.0)).powi(2)).exp();
+    let c_bar_pow_7 = c_bar.powi(7);
+    let r_c = F::TWO * (c_bar_pow_7 / (c_bar_pow_7 + twenty_five_pow_7)).sqrt();
+    let l_bar_minus_50_sqrd = (l_bar - f(50.0)).powi(2);
+    let s_l =
+        F::ONE + f::<F>(0.015) * l_bar_minus_50_sqrd / (f::<F>(20.0) + l_bar_minus_50_sqrd).sqrt();
+    let s_c = F::ONE + f::<F>(0.045) * c_bar;
+    let s_h = F::ONE + f::<F>(0.015) * c_bar * t;
+    let r_t = -(F::TWO * delta_theta).to_radians().sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_h / s_h;
+    (l_term * l_term + c_term * c_term + h_term * h_term + r_t * c_term * h_term).sqrt()
+}
+
+/// The Euclidean distance between two colours in the cylindrical space defined by
+/// their hue, chroma and value.  As values differ by at most 1.0 and the (hue, chroma)
+/// vectors by at most 2.0 (for complementary hues) the result is never more than sqrt(5).
+pub fn hcv_distance<F, C1, C2>(colour_1: &C1, colour_2: &C2) -> F
+where
+    F: ColourComponent,
+    C1: ColourInterface<F> + ?Sized,
+    C2: ColourInterface<F> + ?Sized,
+{
+    let delta_value = colour_1.value() - colour_2.value();
+    let chroma_1 = colour_1.chroma();
+    let chroma_2 = colour_2.chroma();
+    let cos_delta_hue = match (colour_1.hue(), colour_2.hue()) {
+        (Some(hue_1), Some(hue_2)) => (hue_1 - hue_2).cos(),
+        _ => F::ONE,
+    };
+    let chroma_sqrd =
+        chroma_1 * chroma_1 + chroma_2 * chroma_2 - F::TWO * chroma_1 * chroma_2 * cos_delta_hue;
+    (delta_value * delta_value + chroma_sqrd.max(F::ZERO)).sqrt()
+}
+
+/// The difference between two colours as measured by the given metric.
+pub fn difference<F, C1, C2>(colour_1: &C1, colour_2: &C2, metric: DifferenceMetric) -> F
+where
+    F: ColourComponent,
+    C1: ColourInterface<F> + ?Sized,
+    C2: ColourInterface<F> + ?Sized,
+{
+    match metric {
+        DifferenceMetric::CIE76 => delta_e_76(&colour_1.lab(), &colour_2.lab()),
+        DifferenceMetric::CIE94 => delta_e_94(&colour_1.lab(), &colour_2.lab()),
+        DifferenceMetric::CIEDE2000 => delta_e_2000(&colour_1.lab(), &colour_2.lab()),
+        DifferenceMetric::HCV => hcv_distance(colour_1, colour_2),
+    }
+}
+
+#[cfg(test)]
+mod difference_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants, RGB};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn ciede2000_reference_data() {
+        // Selected pairs from the Sharma, Wu and Dalal test data
+        let data: [([f64; 3], [f64; 3], f64); 10] = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, 0.0, 0.0], [50.0, -1.0, 2.0], 2.3669),
+            ([50.0, 2.49, -0.001], [50.0, -2.49, 0.0009], 7.1792),
+            ([50.0, 2.49, -0.001], [50.0, -2.49, 0.0012], 7.2195),
+            ([50.0, -0.001, 2.49], [50.0, 0.0009, -2.49], 4.8045),
+            ([50.0, -0.001, 2.49], [50.0, 0.0011, -2.49], 4.7461),
+            ([50.0, 2.5, 0.0], [50.0, 0.0, -2.5], 4.3065),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            (
+                [60.2574, -34.0099, 36.2677],
+                [60.4626, -34.1751, 39.4387],
+                1.2644,
+            ),
+            (
+                [2.0776, 0.0795, -1.1350],
+                [0.9033, -0.0636, -0.5514],
+                0.9082,
+            ),
+        ];
+        for (lab_1, lab_2, expected) in data.iter() {
+            let lab_1 = Lab::from(*lab_1);
+            let lab_2 = Lab::from(*lab_2);
+            assert_approx_eq!(delta_e_2000(&lab_1, &lab_2), *expected, 0.000_1);
+            assert_approx_eq!(delta_e_2000(&lab_2, &lab_1), *expected, 0.000_1);
+        }
+    }
+
+    #[test]
+    fn cie76_and_cie94() {
+        let lab_1 = Lab::<f64>::from([50.0, 2.5, 0.0]);
+        let lab_2 = Lab::<f64>::from([73.0, 25.0, -18.0]);
+        assert_approx_eq!(delta_e_76(&lab_1, &lab_2), 1359.25_f64.sqrt());
+        assert_approx_eq!(delta_e_76(&lab_2, &lab_1), 1359.25_f64.sqrt());
+        // Only the lightness term differs for greys
+        let grey_1 = Lab::<f64>::from([40.0, 0.0, 0.0]);
+        let grey_2 = Lab::<f64>::from([45.0, 0.0, 0.0]);
+        assert_approx_eq!(delta_e_94(&grey_1, &grey_2), 5.0);
+        assert!(delta_e_2000(&grey_1, &grey_2) < 5.0);
+        assert!(delta_e_94(&lab_1, &lab_2) < delta_e_76(&lab_1, &lab_2));
+    }
+
+    #[test]
+    fn colour_interface_difference() {
+        for metric in [
+            DifferenceMetric::CIE76,
+            DifferenceMetric::CIE94,
+            DifferenceMetric::CIEDE2000,
+            DifferenceMetric::HCV,
+        ]
+        .iter()
+        {
+            for rgb in RGB::<f64>::PRIMARIES.iter().chain(RGB::GREYS.iter()) {
+                assert_approx_eq!(rgb.difference(&rgb.hsv(), *metric), 0.0);
+            }
+            let white_black = RGB::<f64>::WHITE.difference(&RGB::BLACK, *metric);
+            let white_grey = RGB::<f64>::WHITE.difference(&RGB::from([0.5, 0.5, 0.5]), *metric);
+            assert!(white_black > white_grey);
+        }
+        assert_approx_eq!(
+            RGB::<f64>::WHITE.difference(&RGB::BLACK, DifferenceMetric::CIE76),
+            100.0,
+            0.000_001
+        );
+    }
+
+    #[test]
+    fn hcv_distances() {
+        assert_approx_eq!(
+            hcv_distance(&RGB::<f64>::RED, &RGB::CYAN),
+            (1.0_f64 / 9.0 + 4.0).sqrt()
+        );
+        assert_approx_eq!(hcv_distance(&RGB::<f64>::WHITE, &RGB::BLACK), 1.0);
+        let red_green = hcv_distance(&RGB::<f64>::RED, &RGB::GREEN);
+        assert_approx_eq!(red_green, 3.0_f64.sqrt(), 0.000_000_001);
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{
+    css::CSSError, hcv::HCVError, palette::PaletteError, urgb::URGBError, urgba::URGBAError,
+};
+
+/// The errors reported by the fallible (`try_*`) constructors and operations of this
+/// crate together with those of its more specialised error types.
+#[derive(Debug)]
+pub enum ColourMathError {
+    /// A component or parameter outside the range permitted for it.
+    OutOfRange(String),
+    /// A slice or vector (e.g. the components of a colour or the pixels of an image)
+    /// with an inappropriate length.
+    BadLength(String),
+    CSS(CSSError),
+    HCV(HCVError),
+    Palette(PaletteError),
+    URGB(URGBError),
+    URGBA(URGBAError),
+}
+
+impl std::fmt::Display for ColourMathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColourMathError::OutOfRange(string) => write!(f, "Out of range: {}", string),
+            ColourMathError::BadLength(string) => write!(f, "Bad length: {}", string),
+            ColourMathError::CSS(error) => error.fmt(f),
+            ColourMathError::HCV(error) => error.fmt(f),
+            ColourMathError::Palette(error) => error.fmt(f),
+            ColourMathError::URGB(error) => error.fmt(f),
+            ColourMathError::URGBA(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ColourMathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ColourMathError::CSS(error) => Some(error),
+            ColourMathError::HCV(error) => Some(error),
+            ColourMathError::Palette(error) => Some(error),
+            ColourMathError::URGB(error) => Some(error),
+            ColourMathError::URGBA(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<CSSError> for ColourMathError {
+    fn from(error: CSSError) -> Self {
+        ColourMathError::CSS(error)
+    }
+}
+
+impl From<HCVError> for ColourMathError {
+    fn from(error: HCVError) -> Self {
+        ColourMathError::HCV(error)
+    }
+}
+
+impl From<PaletteError> for ColourMathError {
+    fn from(error: PaletteError) -> Self {
+        ColourMathError::Palette(error)
+    }
+}
+
+impl From<URGBError> for ColourMathError {
+    fn from(error: URGBError) -> Self {
+        ColourMathError::URGB(error)
+    }
+}
+
+impl From<URGBAError> for ColourMathError {
+    fn from(error: URGBAError) -> Self {
+        ColourMathError::URGBA(error)
+    }
+}
+
+/// Check that all of `components` are in the range 0.0 to 1.0.
+pub(crate) fn check_proportions<F: crate::ColourComponent>(
+    what: &str,
+    components: &[F],
+) -> Result<(), ColourMathError> {
+    if components.iter().all(|c| c.is_proportion()) {
+        Ok(())
+    } else {
+        Err(ColourMathError::OutOfRange(format!(
+            "{}: {:?} (must be in the range 0.0 to 1.0)",
+            what, components
+        )))
+    }
+}
+
+/// Check that `length` is exactly `expected`.
+pub(crate) fn check_length(
+    what: &str,
+    length: usize,
+    expected: usize,
+) -> Result<(), ColourMathError> {
+    if length == expected {
+        Ok(())
+    } else {
+        Err(ColourMathError::BadLength(format!(
+            "{}: {} (expected {})",
+            what, length, expected
+        )))
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use super::*;
+    use std::error::Error;
+    use std::str::FromStr;
+
+    #[test]
+    fn conversions_and_messages() {
+        let error: ColourMathError = crate::URGB::<u8>::from_str("not a colour")
+            .unwrap_err()
+            .into();
+        assert!(matches!(error, ColourMathError::URGB(_)));
+        assert!(error.source().is_some());
+        let error: ColourMathError = crate::RGB::<f64>::from_str("rgb(").unwrap_err().into();
+        assert!(matches!(error, ColourMathError::CSS(_)));
+        let error = check_proportions("RGB", &[0.5, 1.5, 0.0]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Out of range: RGB: [0.5, 1.5, 0.0] (must be in the range 0.0 to 1.0)"
+        );
+        assert!(error.source().is_none());
+        assert!(check_proportions("RGB", &[0.5, 1.0, 0.0]).is_ok());
+        assert!(check_proportions("RGB", &[f64::NAN]).is_err());
+        assert_eq!(
+            check_length("pixels", 4, 3).unwrap_err().to_string(),
+            "Bad length: pixels: 4 (expected 3)"
+        );
+    }
+}
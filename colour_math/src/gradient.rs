@@ -0,0 +1,309 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{
+    chroma::HueData, clamped, linear_rgb::LinearRGB, ColourComponent, Degrees, HueIfce, HCV, RGB,
+};
+
+/// Which way around the hue circle to go when interpolating hues.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HuePath {
+    Shortest,
+    Longest,
+}
+
+/// The colour space in which colours between stops are interpolated.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// Gamma-encoded RGB (as done by most drawing libraries)
+    RGB,
+    /// Linear light RGB
+    LinearRGB,
+    /// Hue, chroma and value with chroma favoured (over value) when they conflict
+    HCV(HuePath),
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Interpolation::RGB
+    }
+}
+
+/// Easing functions applied to the position within each segment of a gradient.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    SmoothStep,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Map `t` (in the range 0.0 to 1.0) to the eased position.
+    pub fn apply<F: ColourComponent>(&self, t: F) -> F {
+        debug_assert!(t.is_proportion(), "t: {:?}", t);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => F::ONE - (F::ONE - t) * (F::ONE - t),
+            Easing::EaseInOut => {
+                if t < F::HALF {
+                    F::TWO * t * t
+                } else {
+                    F::ONE - (F::TWO - F::TWO * t).powi(2) / F::TWO
+                }
+            }
+            Easing::SmoothStep => t * t * (F::THREE - F::TWO * t),
+        }
+    }
+}
+
+fn lerp<F: ColourComponent>(start: F, end: F, t: F) -> F {
+    start + (end - start) * t
+}
+
+fn interpolate_hcv<F: ColourComponent>(
+    start: &RGB<F>,
+    end: &RGB<F>,
+    t: F,
+    path: HuePath,
+) -> RGB<F> {
+    let start_hcv = HCV::from(start);
+    let end_hcv = HCV::from(end);
+    let chroma = lerp(start_hcv.chroma(), end_hcv.chroma(), t);
+    let value = lerp(start_hcv.value(), end_hcv.value(), t).min(F::ONE);
+    // A grey takes on the hue of the other colour
+    let angle = match (start_hcv.hue_angle(), end_hcv.hue_angle()) {
+        (Some(start_angle), Some(end_angle)) => {
+            let mut delta = (end_angle - start_angle).degrees();
+            if path == HuePath::Longest && delta != F::ZERO {
+                if delta > F::ZERO {
+                    delta -= F::DEG_360;
+                } else {
+                    delta += F::DEG_360;
+                }
+            }
+            Degrees::from(start_angle.degrees() + delta * t)
+        }
+        (Some(angle), None) | (None, Some(angle)) => angle,
+        (None, None) => return [value, value, value].into(),
+    };
+    let hue_data = HueData::<F>::from(angle);
+    let (min_value, max_value) = hue_data.value_range_for_chroma(chroma);
+    let value = value.max(min_value).min(max_value);
+    hue_data
+        .rgb_for_value_and_chroma(value, chroma)
+        .unwrap_or_else(|| hue_data.max_chroma_rgb_for_value(value))
+}
+
+/// A colour gradient defined by colour stops (at offsets in the range 0.0 to 1.0) that
+/// can be sampled at any offset.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Gradient<F: ColourComponent> {
+    stops: Vec<(RGB<F>, F)>,
+    interpolation: Interpolation,
+    easing: Easing,
+}
+
+impl<F: ColourComponent> Gradient<F> {
+    /// Number of samples per segment used by `colour_stops()` when the interpolation or
+    /// easing can't be reproduced by linear RGB interpolation.
+    pub const SAMPLES_PER_SEGMENT: usize = 16;
+
+    /// Create a gradient from the given stops (which will be sorted by offset).
+    /// There must be at least one stop.
+    pub fn new(stops: &[(RGB<F>, F)]) -> Self {
+        assert!(
+            !stops.is_empty(),
+            "a gradient needs at least one colour stop"
+        );
+        debug_assert!(stops.iter().all(|(_, offset)| offset.is_proportion()));
+        let mut stops = stops.to_vec();
+        stops.sort_by(|a, b| a.1.partial_cmp(&b.1).expect("offsets are not NaN"));
+        Self {
+            stops,
+            interpolation: Interpolation::default(),
+            easing: Easing::default(),
+        }
+    }
+
+    /// Stops evenly spaced between 0.0 and 1.0.
+    pub fn evenly_spaced(rgbs: &[RGB<F>]) -> Self {
+        let last = F::from(rgbs.len().max(2) - 1).unwrap();
+        let stops: Vec<(RGB<F>, F)> = rgbs
+            .iter()
+            .enumerate()
+            .map(|(i, rgb)| (*rgb, F::from(i).unwrap() / last))
+            .collect();
+        Self::new(&stops)
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn stops(&self) -> &[(RGB<F>, F)] {
+        &self.stops
+    }
+
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    pub fn easing(&self) -> Easing {
+        self.easing
+    }
+
+    fn interpolate(&self, start: &RGB<F>, end: &RGB<F>, t: F) -> RGB<F> {
+        let t = self.easing.apply(t);
+        match self.interpolation {
+            Interpolation::RGB => *start * (F::ONE - t) + *end * t,
+            Interpolation::LinearRGB => LinearRGB::from(start)
+                .mixed_with(&LinearRGB::from(end), t)
+                .into(),
+            Interpolation::HCV(path) => interpolate_hcv(start, end, t, path),
+        }
+    }
+
+    /// The colour at `offset` (which is clamped to the range 0.0 to 1.0).
+    pub fn sample(&self, offset: F) -> RGB<F> {
+        let offset = clamped(offset);
+        let mut previous = &self.stops[0];
+        if offset <= previous.1 {
+            return previous.0;
+        }
+        for stop in self.stops[1..].iter() {
+            if offset <= stop.1 {
+                let t = (offset - previous.1) / (stop.1 - previous.1);
+                return self.interpolate(&previous.0, &stop.0, t);
+            }
+            previous = stop;
+        }
+        previous.0
+    }
+
+    /// Colour stops that reproduce this gradient (closely) when interpolated linearly in
+    /// RGB e.g. by a drawing library.
+    pub fn colour_stops(&self) -> Vec<(RGB<F>, F)> {
+        if self.interpolation == Interpolation::RGB && self.easing == Easing::Linear {
+            return self.stops.clone();
+        }
+        let samples = F::from(Self::SAMPLES_PER_SEGMENT).unwrap();
+        let mut stops = vec![self.stops[0]];
+        for pair in self.stops.windows(2) {
+            let (start, end) = (&pair[0], &pair[1]);
+            if end.1 > start.1 {
+                for i in 1..Self::SAMPLES_PER_SEGMENT {
+                    let t = F::from(i).unwrap() / samples;
+                    let rgb = self.interpolate(&start.0, &end.0, t);
+                    stops.push((rgb, lerp(start.1, end.1, t)));
+                }
+            }
+            stops.push(*end);
+        }
+        stops
+    }
+}
+
+impl<F: ColourComponent> From<&[(RGB<F>, F)]> for Gradient<F> {
+    fn from(stops: &[(RGB<F>, F)]) -> Self {
+        Self::new(stops)
+    }
+}
+
+impl<F: ColourComponent> From<Vec<(RGB<F>, F)>> for Gradient<F> {
+    fn from(stops: Vec<(RGB<F>, F)>) -> Self {
+        Self::new(&stops)
+    }
+}
+
+#[cfg(test)]
+mod gradient_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn easing_end_points() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::SmoothStep,
+        ]
+        .iter()
+        {
+            assert_eq!(easing.apply(0.0_f64), 0.0);
+            assert_eq!(easing.apply(1.0_f64), 1.0);
+            assert!(easing.apply(0.25_f64) <= easing.apply(0.75_f64));
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5_f64), 0.5);
+        assert!(Easing::EaseIn.apply(0.5_f64) < 0.5);
+        assert!(Easing::EaseOut.apply(0.5_f64) > 0.5);
+    }
+
+    #[test]
+    fn rgb_sampling() {
+        let gradient = Gradient::<f64>::new(&[(RGB::WHITE, 1.0), (RGB::BLACK, 0.0)]);
+        assert_eq!(gradient.stops()[0], (RGB::BLACK, 0.0));
+        assert_eq!(gradient.sample(-1.0), RGB::BLACK);
+        assert_eq!(gradient.sample(2.0), RGB::WHITE);
+        assert_approx_eq!(gradient.sample(0.25), RGB::from([0.25, 0.25, 0.25]));
+        assert_eq!(gradient.colour_stops(), gradient.stops().to_vec());
+        let gradient = gradient.with_interpolation(Interpolation::LinearRGB);
+        assert_approx_eq!(gradient.sample(0.5).linear_value(), 0.5, 0.000_000_001);
+        let single = Gradient::<f64>::new(&[(RGB::RED, 0.5)]);
+        assert_eq!(single.sample(0.0), RGB::RED);
+        assert_eq!(single.sample(1.0), RGB::RED);
+    }
+
+    #[test]
+    fn hcv_sampling() {
+        let gradient = Gradient::<f64>::evenly_spaced(&[RGB::RED, RGB::GREEN])
+            .with_interpolation(Interpolation::HCV(HuePath::Shortest));
+        assert_approx_eq!(gradient.sample(0.5), RGB::YELLOW, 0.000_000_001);
+        let gradient = gradient.with_interpolation(Interpolation::HCV(HuePath::Longest));
+        assert_approx_eq!(gradient.sample(0.5), RGB::BLUE, 0.000_000_001);
+        for i in 0..=10 {
+            let rgb = gradient.sample(i as f64 / 10.0);
+            assert_approx_eq!(rgb.chroma(), 1.0, 0.000_000_001);
+        }
+        // A grey end point only changes chroma and value
+        let gradient = Gradient::<f64>::evenly_spaced(&[RGB::BLUE, RGB::WHITE])
+            .with_interpolation(Interpolation::HCV(HuePath::Shortest));
+        let rgb = gradient.sample(0.5);
+        assert_approx_eq!(
+            rgb.hue_angle().unwrap(),
+            RGB::<f64>::BLUE.hue_angle().unwrap(),
+            0.000_000_001
+        );
+        assert_approx_eq!(rgb.chroma(), 0.5, 0.000_000_001);
+    }
+
+    #[test]
+    fn colour_stops_for_drawing() {
+        let gradient = Gradient::<f64>::evenly_spaced(&[RGB::RED, RGB::CYAN, RGB::RED])
+            .with_interpolation(Interpolation::HCV(HuePath::Shortest))
+            .with_easing(Easing::SmoothStep);
+        let stops = gradient.colour_stops();
+        assert_eq!(stops.len(), 2 * Gradient::<f64>::SAMPLES_PER_SEGMENT + 1);
+        assert_eq!(stops[0], (RGB::RED, 0.0));
+        assert_eq!(stops[stops.len() - 1], (RGB::RED, 1.0));
+        for pair in stops.windows(2) {
+            assert!(pair[0].1 < pair[1].1);
+        }
+    }
+}
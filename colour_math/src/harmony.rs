@@ -0,0 +1,155 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{
+    chroma::HueData, manipulator::RotationPolicy, ColourComponent, ColourInterface, Degrees,
+    HueIfce, RGB,
+};
+
+/// Classical colour harmonies defined by hue rotations of a base colour.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Harmony {
+    /// The base colour and the colour opposite it
+    Complementary,
+    /// The base colour and the two colours either side of its complement
+    SplitComplementary,
+    /// Three colours evenly spaced around the hue circle
+    Triadic,
+    /// Two complementary pairs forming a rectangle (60 degrees apart)
+    Tetradic,
+    /// Four colours evenly spaced around the hue circle
+    Square,
+    /// The base colour and its neighbours (30 degrees either side)
+    Analogous,
+}
+
+impl Harmony {
+    pub const ALL: [Harmony; 6] = [
+        Harmony::Complementary,
+        Harmony::SplitComplementary,
+        Harmony::Triadic,
+        Harmony::Tetradic,
+        Harmony::Square,
+        Harmony::Analogous,
+    ];
+
+    /// The hue rotations (from the base colour) of the other members of the harmony.
+    pub fn rotations<F: ColourComponent>(&self) -> Vec<Degrees<F>> {
+        let degrees: &[f64] = match self {
+            Harmony::Complementary => &[180.0],
+            Harmony::SplitComplementary => &[150.0, -150.0],
+            Harmony::Triadic => &[120.0, -120.0],
+            Harmony::Tetradic => &[60.0, 180.0, -120.0],
+            Harmony::Square => &[90.0, 180.0, -90.0],
+            Harmony::Analogous => &[30.0, -30.0],
+        };
+        degrees
+            .iter()
+            .map(|d| Degrees::from(F::from(*d).unwrap()))
+            .collect()
+    }
+}
+
+/// The members of the `harmony` based on `colour` (which is the first member).  The
+/// other members have the same chroma as `colour` with the value adjusted only if
+/// necessary (`RotationPolicy::FavourChroma`) or the same value with the chroma
+/// reduced only if necessary (`RotationPolicy::FavourValue`).  All members of a grey's
+/// harmony are that grey.
+pub fn harmony_rgbs<F: ColourComponent>(
+    colour: &impl ColourInterface<F>,
+    harmony: Harmony,
+    policy: RotationPolicy,
+) -> Vec<RGB<F>> {
+    let base_rgb = colour.rgb();
+    let mut rgbs = vec![base_rgb];
+    let rotations = harmony.rotations::<F>();
+    if let Some(hue) = colour.hue() {
+        let hcv = colour.hcv();
+        let (chroma, value) = (hcv.chroma(), hcv.value());
+        for rotation in rotations.iter() {
+            let hue_data = HueData::<F>::from((hue + *rotation).angle());
+            let (chroma, value) = match policy {
+                RotationPolicy::FavourChroma => {
+                    let (min_value, max_value) = hue_data.value_range_for_chroma(chroma);
+                    (chroma, value.max(min_value).min(max_value))
+                }
+                RotationPolicy::FavourValue => {
+                    (chroma.min(hue_data.max_chroma_for_value(value)), value)
+                }
+            };
+            let rgb = hue_data
+                .rgb_for_value_and_chroma(value, chroma)
+                .unwrap_or_else(|| hue_data.max_chroma_rgb_for_value(value));
+            rgbs.push(rgb);
+        }
+    } else {
+        rgbs.resize(rotations.len() + 1, base_rgb);
+    }
+    rgbs
+}
+
+#[cfg(test)]
+mod harmony_tests {
+    use super::*;
+    use crate::{HueConstants, RGBConstants, HCV};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn member_counts() {
+        for harmony in Harmony::ALL.iter() {
+            let expected = harmony.rotations::<f64>().len() + 1;
+            for policy in [RotationPolicy::FavourChroma, RotationPolicy::FavourValue].iter() {
+                let rgbs = harmony_rgbs(&RGB::<f64>::RED, *harmony, *policy);
+                assert_eq!(rgbs.len(), expected);
+                assert_eq!(rgbs[0], RGB::RED);
+                let rgbs = harmony_rgbs(&RGB::<f64>::WHITE, *harmony, *policy);
+                assert_eq!(rgbs, vec![RGB::WHITE; expected]);
+            }
+        }
+    }
+
+    #[test]
+    fn primaries_and_secondaries() {
+        let rgbs = harmony_rgbs(
+            &RGB::<f64>::RED,
+            Harmony::Complementary,
+            RotationPolicy::FavourChroma,
+        );
+        assert_approx_eq!(rgbs[1], RGB::CYAN, 0.000_000_001);
+        let rgbs = harmony_rgbs(
+            &RGB::<f64>::RED,
+            Harmony::Triadic,
+            RotationPolicy::FavourChroma,
+        );
+        assert_approx_eq!(rgbs[1], RGB::GREEN, 0.000_000_001);
+        assert_approx_eq!(rgbs[2], RGB::BLUE, 0.000_000_001);
+    }
+
+    #[test]
+    fn policies() {
+        let base = RGB::<f64>::from([0.8, 0.3, 0.2]);
+        let base_hcv = HCV::from(base);
+        for harmony in Harmony::ALL.iter() {
+            let rgbs = harmony_rgbs(&base, *harmony, RotationPolicy::FavourChroma);
+            for rgb in rgbs.iter() {
+                assert_approx_eq!(HCV::from(rgb).chroma(), base_hcv.chroma(), 0.000_000_1);
+            }
+            let rgbs = harmony_rgbs(&base, *harmony, RotationPolicy::FavourValue);
+            for rgb in rgbs.iter() {
+                let hcv = HCV::from(rgb);
+                assert_approx_eq!(hcv.value(), base_hcv.value(), 0.000_000_1);
+                assert!(hcv.chroma() <= base_hcv.chroma() + 0.000_000_1);
+            }
+        }
+    }
+
+    #[test]
+    fn hue_rotations() {
+        let base = RGB::<f64>::from([0.6, 0.5, 0.2]);
+        let base_angle = base.hue_angle().unwrap();
+        let rgbs = harmony_rgbs(&base, Harmony::Square, RotationPolicy::FavourValue);
+        for (rgb, rotation) in rgbs[1..].iter().zip(Harmony::Square.rotations().iter()) {
+            let angle = rgb.hue_angle().unwrap();
+            assert_approx_eq!(angle, base_angle + *rotation, 0.000_000_1);
+        }
+    }
+}
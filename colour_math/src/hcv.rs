@@ -1,11 +1,17 @@
 // Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
-use std::marker::PhantomData;
+use std::{
+    convert::{TryFrom, TryInto},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+use regex::Regex;
 
 use crate::chroma::HueData;
 use crate::urgb::UnsignedComponent;
 use crate::{
-    chroma, image, ColourComponent, ColourInterface, HueConstants, HueIfce, IndicesValueOrder,
-    RGBConstants, CCI, RGB, URGB,
+    chroma, clamped, contrast, error::ColourMathError, image, ColourComponent, ColourInterface,
+    Hue, HueConstants, HueIfce, IndicesValueOrder, RGBConstants, CCI, RGB, RGBA, URGB,
 };
 use normalised_angles::Degrees;
 use std::cmp::Ordering;
@@ -57,6 +63,45 @@ impl<F: ColourComponent> HCV<F> {
     pub fn is_grey(&self) -> bool {
         self.hue_data.is_none()
     }
+
+    /// The HCV with the given hue angle, chroma and value (or `None` if they don't
+    /// describe a valid colour).  The hue is ignored for greys (zero chroma) and
+    /// required otherwise.
+    pub fn with_hue_chroma_value(hue: Option<Degrees<F>>, chroma: F, value: F) -> Option<Self> {
+        if !(chroma.is_proportion() && value.is_proportion()) {
+            return None;
+        }
+        if chroma == F::ZERO {
+            return Some(Self {
+                hue_data: None,
+                chroma,
+                sum: value * F::THREE,
+            });
+        }
+        let hue_data = HueData::<F>::from(hue?);
+        // allow for rounding errors in values that have been written out as text
+        let tolerance = F::from(0.000_000_001).unwrap();
+        let (min_value, max_value) = hue_data.value_range_for_chroma(chroma);
+        if value < min_value - tolerance || value > max_value + tolerance {
+            return None;
+        }
+        Some(Self {
+            hue_data: Some(hue_data),
+            chroma,
+            sum: value.max(min_value).min(max_value) * F::THREE,
+        })
+    }
+
+    /// As `with_hue_chroma_value()` but with an error describing invalid input.
+    pub fn try_new(hue: Option<Degrees<F>>, chroma: F, value: F) -> Result<Self, ColourMathError> {
+        Self::with_hue_chroma_value(hue, chroma, value).ok_or_else(|| {
+            HCVError::OutOfGamut(format!(
+                "hue: {:?}, chroma: {:?}, value: {:?}",
+                hue, chroma, value
+            ))
+            .into()
+        })
+    }
 }
 
 impl<F: ColourComponent> HueConstants for HCV<F> {
@@ -218,11 +263,153 @@ impl ChromaTolerance for f64 {
     const COMA_TOLERANCE: Self = 0.000_000_000_01;
 }
 
-impl<F: ColourComponent + ChromaTolerance> From<&HCV<F>> for RGB<F> {
-    fn from(hcv: &HCV<F>) -> Self {
+#[derive(Debug, PartialEq, Eq)]
+pub enum HCVError {
+    OutOfGamut(String),
+    MalformedText(String),
+}
+
+impl std::fmt::Display for HCVError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HCVError::OutOfGamut(string) => {
+                write!(f, "HCV does not represent a valid colour: {}", string)
+            }
+            HCVError::MalformedText(string) => write!(f, "Malformed text: {}", string),
+        }
+    }
+}
+
+impl std::error::Error for HCVError {}
+
+/// Strategies for bringing an HCV whose chroma and value are incompatible with its
+/// hue (or each other) into the RGB gamut.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HCVGamutMapping {
+    /// Keep the hue and value and reduce the chroma to the maximum possible.
+    ReduceChroma,
+    /// Keep the hue and chroma and move the value into the range possible.
+    AdjustValue,
+    /// Keep the hue and move to the nearest point in the chroma/value plane.
+    Nearest,
+}
+
+impl Default for HCVGamutMapping {
+    fn default() -> Self {
+        HCVGamutMapping::ReduceChroma
+    }
+}
+
+impl<F: ColourComponent + ChromaTolerance> HCV<F> {
+    fn out_of_gamut(&self) -> HCVError {
+        HCVError::OutOfGamut(format!("{:?}", self))
+    }
+
+    /// Whether this HCV represents an actual colour.
+    pub fn is_valid(&self) -> bool {
+        RGB::try_from(self).is_ok()
+    }
+
+    /// A valid HCV with the same hue (if any) derived from this one using `mapping`.
+    pub fn gamut_mapped(&self, mapping: HCVGamutMapping) -> Self {
+        let sum = if self.sum.is_nan() {
+            F::ZERO
+        } else {
+            self.sum.max(F::ZERO).min(F::THREE)
+        };
+        let chroma = if self.chroma.is_nan() {
+            F::ZERO
+        } else {
+            clamped(self.chroma)
+        };
+        let hue_data = match self.hue_data {
+            Some(hue_data) if chroma > F::ZERO && sum > F::ZERO && sum < F::THREE => hue_data,
+            _ => match mapping {
+                HCVGamutMapping::ReduceChroma | HCVGamutMapping::Nearest => {
+                    return Self {
+                        hue_data: None,
+                        chroma: F::ZERO,
+                        sum,
+                    }
+                }
+                HCVGamutMapping::AdjustValue => match self.hue_data {
+                    Some(hue_data) if chroma > F::ZERO => hue_data,
+                    _ => {
+                        return Self {
+                            hue_data: None,
+                            chroma: F::ZERO,
+                            sum,
+                        }
+                    }
+                },
+            },
+        };
+        let (min_sum, max_sum) = hue_data.sum_range_for_chroma(chroma);
+        if sum >= min_sum && sum <= max_sum {
+            return Self {
+                hue_data: Some(hue_data),
+                chroma,
+                sum,
+            };
+        }
+        let (chroma, sum) = match mapping {
+            HCVGamutMapping::ReduceChroma => (hue_data.max_chroma_for_sum(sum), sum),
+            HCVGamutMapping::AdjustValue => (chroma, sum.max(min_sum).min(max_sum)),
+            HCVGamutMapping::Nearest => {
+                // the gamut is the triangle (black, white, max chroma) in the chroma/value
+                // plane so the nearest point is on one of the edges from the max chroma
+                let value = sum / F::THREE;
+                let apex_value = hue_data.sum_range_for_chroma(F::ONE).0 / F::THREE;
+                let end_value = if value < apex_value * chroma {
+                    F::ZERO
+                } else {
+                    F::ONE
+                };
+                let (dc, dv) = (F::ONE, apex_value - end_value);
+                let t = clamped((chroma * dc + (value - end_value) * dv) / (dc * dc + dv * dv));
+                (t, (end_value + t * dv) * F::THREE)
+            }
+        };
+        if chroma > F::ZERO {
+            Self {
+                hue_data: Some(hue_data),
+                chroma,
+                sum,
+            }
+        } else {
+            Self {
+                hue_data: None,
+                chroma: F::ZERO,
+                sum,
+            }
+        }
+    }
+
+    /// The RGB for this HCV after applying `mapping` (if necessary).
+    pub fn rgb_with_mapping(&self, mapping: HCVGamutMapping) -> RGB<F> {
+        let hcv = self.gamut_mapped(mapping);
+        match hcv.hue_data {
+            Some(hue_data) => hue_data
+                .rgb_for_sum_and_chroma(hcv.sum, hcv.chroma)
+                .unwrap_or_else(|| hue_data.max_chroma_rgb_for_sum(hcv.sum)),
+            None => {
+                let value = hcv.sum / F::THREE;
+                RGB::from([value, value, value])
+            }
+        }
+    }
+}
+
+impl<F: ColourComponent + ChromaTolerance> TryFrom<&HCV<F>> for RGB<F> {
+    type Error = HCVError;
+
+    fn try_from(hcv: &HCV<F>) -> Result<Self, Self::Error> {
+        if !(hcv.sum >= F::ZERO && hcv.sum <= F::THREE && hcv.chroma.is_proportion()) {
+            return Err(hcv.out_of_gamut());
+        }
         if let Some(hue_data) = hcv.hue_data {
             if let Some(rgb) = hue_data.rgb_for_sum_and_chroma(hcv.sum, hcv.chroma) {
-                rgb
+                Ok(rgb)
             } else {
                 // This can possibly be due floating point arithmetic's inability to properly
                 // represent reals resulting in the HCV having a chroma value slightly higher
@@ -231,23 +418,25 @@ impl<F: ColourComponent + ChromaTolerance> From<&HCV<F>> for RGB<F> {
                 // chroma and if so use that.
                 let rgb = hue_data.max_chroma_rgb_for_sum(hcv.sum);
                 if rgb.chroma().approx_eq(&hcv.chroma, Some(F::COMA_TOLERANCE)) {
-                    rgb
+                    Ok(rgb)
                 } else {
-                    panic!("This HCV does not represent a valid colour")
+                    Err(hcv.out_of_gamut())
                 }
             }
-        } else {
-            debug_assert_eq!(hcv.chroma, F::ZERO);
+        } else if hcv.chroma == F::ZERO {
             let value = hcv.sum / F::THREE;
-            debug_assert!(value >= F::ZERO && value <= F::ONE);
-            RGB::from([value, value, value])
+            Ok(RGB::from([value, value, value]))
+        } else {
+            Err(hcv.out_of_gamut())
         }
     }
 }
 
-impl<F: ColourComponent + ChromaTolerance> From<HCV<F>> for RGB<F> {
-    fn from(hcv: HCV<F>) -> Self {
-        RGB::from(&hcv)
+impl<F: ColourComponent + ChromaTolerance> TryFrom<HCV<F>> for RGB<F> {
+    type Error = HCVError;
+
+    fn try_from(hcv: HCV<F>) -> Result<Self, Self::Error> {
+        RGB::try_from(&hcv)
     }
 }
 
@@ -258,10 +447,135 @@ impl<U: UnsignedComponent, F: ColourComponent + ChromaTolerance> From<&URGB<U>>
     }
 }
 
-impl<U: UnsignedComponent, F: ColourComponent + ChromaTolerance> From<&HCV<F>> for URGB<U> {
-    fn from(hcv: &HCV<F>) -> Self {
-        let rgb: RGB<F> = hcv.into();
-        Self::from(rgb)
+impl<U: UnsignedComponent, F: ColourComponent + ChromaTolerance> TryFrom<&HCV<F>> for URGB<U> {
+    type Error = HCVError;
+
+    fn try_from(hcv: &HCV<F>) -> Result<Self, Self::Error> {
+        let rgb: RGB<F> = hcv.try_into()?;
+        Ok(Self::from(rgb))
+    }
+}
+
+/// Invalid HCVs are treated as their gamut mapped (with chroma reduction)
+/// equivalents when RGB is required.
+impl<F: ColourComponent + ChromaTolerance> ColourInterface<F> for HCV<F> {
+    fn rgb(&self) -> RGB<F> {
+        self.rgb_with_mapping(HCVGamutMapping::default())
+    }
+
+    fn rgba(&self) -> RGBA<F> {
+        self.rgb().rgba()
+    }
+
+    fn hcv(&self) -> HCV<F> {
+        *self
+    }
+
+    fn hue(&self) -> Option<Hue<F>> {
+        self.hue_angle().map(Hue::from)
+    }
+
+    fn hue_angle(&self) -> Option<Degrees<F>> {
+        HCV::hue_angle(self)
+    }
+
+    fn is_grey(&self) -> bool {
+        HCV::is_grey(self)
+    }
+
+    fn chroma(&self) -> F {
+        self.chroma
+    }
+
+    fn greyness(&self) -> F {
+        F::ONE - self.chroma
+    }
+
+    fn value(&self) -> F {
+        HCV::value(self)
+    }
+
+    fn warmth(&self) -> F {
+        ((self.x() + F::ONE).max(F::ZERO) / F::TWO).min(F::ONE)
+    }
+
+    fn best_foreground_rgb(&self) -> RGB<F> {
+        contrast::best_foreground_rgb_from(&self.rgb(), &[RGB::BLACK, RGB::WHITE])
+            .expect("not empty")
+    }
+
+    fn monochrome_rgb(&self) -> RGB<F> {
+        let value = HCV::value(self);
+        [value, value, value].into()
+    }
+
+    fn max_chroma_rgb(&self) -> RGB<F> {
+        match self.hue_data {
+            Some(hue_data) => hue_data.max_chroma_rgb(),
+            None => self.monochrome_rgb(),
+        }
+    }
+
+    fn warmth_rgb(&self) -> RGB<F> {
+        let x = self.x();
+        if x < F::ZERO {
+            let temp = x.abs() + (F::ONE + x) * F::HALF;
+            [F::ZERO, temp, temp].into()
+        } else if x > F::ZERO {
+            [x + (F::ONE - x) * F::HALF, F::ZERO, F::ZERO].into()
+        } else {
+            [F::HALF, F::HALF, F::HALF].into()
+        }
+    }
+}
+
+lazy_static! {
+    pub static ref HCV_RE: Regex = Regex::new(
+        r#"^\s*HCV\(\s*hue\s*=\s*(?P<hue>None|\S+?)\s*,\s*chroma\s*=\s*(?P<chroma>\S+?)\s*,\s*value\s*=\s*(?P<value>\S+?)\s*\)\s*$"#
+    ).unwrap();
+}
+
+impl<F: ColourComponent> std::fmt::Display for HCV<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.value().to_f64().unwrap();
+        let chroma = self.chroma.to_f64().unwrap();
+        if let Some(hue_angle) = self.hue_angle() {
+            let hue = hue_angle.degrees().to_f64().unwrap();
+            write!(
+                f,
+                "HCV(hue={:?}, chroma={:?}, value={:?})",
+                hue, chroma, value
+            )
+        } else {
+            write!(f, "HCV(hue=None, chroma={:?}, value={:?})", chroma, value)
+        }
+    }
+}
+
+/// Accepts the format produced by `Display` (e.g. `HCV(hue=30.0, chroma=0.5, value=0.6)`)
+/// with the hue given as `None` for greys.
+impl<F: ColourComponent> FromStr for HCV<F> {
+    type Err = HCVError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let malformed = || HCVError::MalformedText(string.to_string());
+        let captures = HCV_RE.captures(string).ok_or_else(malformed)?;
+        let number = |name: &str| -> Result<F, HCVError> {
+            let number = f64::from_str(&captures[name]).map_err(|_| malformed())?;
+            F::from(number).ok_or_else(malformed)
+        };
+        let hue = match &captures["hue"] {
+            "None" => None,
+            _ => Some(Degrees::from(number("hue")?)),
+        };
+        let chroma = number("chroma")?;
+        let value = number("value")?;
+        Self::with_hue_chroma_value(hue, chroma, value).ok_or_else(|| {
+            HCVError::OutOfGamut(format!(
+                "hue: {:?}, chroma: {:?}, value: {:?}",
+                hue, chroma, value
+            ))
+        })
     }
 }
 
@@ -317,26 +631,56 @@ mod hcv_tests {
 
     #[test]
     fn create_rgb_consts() {
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::RED), RGB::RED);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::GREEN), RGB::GREEN);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::BLUE), RGB::BLUE);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::CYAN), RGB::CYAN);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::MAGENTA), RGB::MAGENTA);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::YELLOW), RGB::YELLOW);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::WHITE), RGB::WHITE);
-        assert_eq!(RGB::<f64>::from(&HCV::<f64>::BLACK), RGB::BLACK);
+        assert_eq!(RGB::<f64>::try_from(&HCV::<f64>::RED).unwrap(), RGB::RED);
+        assert_eq!(
+            RGB::<f64>::try_from(&HCV::<f64>::GREEN).unwrap(),
+            RGB::GREEN
+        );
+        assert_eq!(RGB::<f64>::try_from(&HCV::<f64>::BLUE).unwrap(), RGB::BLUE);
+        assert_eq!(RGB::<f64>::try_from(&HCV::<f64>::CYAN).unwrap(), RGB::CYAN);
+        assert_eq!(
+            RGB::<f64>::try_from(&HCV::<f64>::MAGENTA).unwrap(),
+            RGB::MAGENTA
+        );
+        assert_eq!(
+            RGB::<f64>::try_from(&HCV::<f64>::YELLOW).unwrap(),
+            RGB::YELLOW
+        );
+        assert_eq!(
+            RGB::<f64>::try_from(&HCV::<f64>::WHITE).unwrap(),
+            RGB::WHITE
+        );
+        assert_eq!(
+            RGB::<f64>::try_from(&HCV::<f64>::BLACK).unwrap(),
+            RGB::BLACK
+        );
     }
 
     #[test]
     fn create_urgb_consts() {
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::RED), URGB::RED);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::GREEN), URGB::GREEN);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::BLUE), URGB::BLUE);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::CYAN), URGB::CYAN);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::MAGENTA), URGB::MAGENTA);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::YELLOW), URGB::YELLOW);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::WHITE), URGB::WHITE);
-        assert_eq!(URGB::<u8>::from(&HCV::<f64>::BLACK), URGB::BLACK);
+        assert_eq!(URGB::<u8>::try_from(&HCV::<f64>::RED).unwrap(), URGB::RED);
+        assert_eq!(
+            URGB::<u8>::try_from(&HCV::<f64>::GREEN).unwrap(),
+            URGB::GREEN
+        );
+        assert_eq!(URGB::<u8>::try_from(&HCV::<f64>::BLUE).unwrap(), URGB::BLUE);
+        assert_eq!(URGB::<u8>::try_from(&HCV::<f64>::CYAN).unwrap(), URGB::CYAN);
+        assert_eq!(
+            URGB::<u8>::try_from(&HCV::<f64>::MAGENTA).unwrap(),
+            URGB::MAGENTA
+        );
+        assert_eq!(
+            URGB::<u8>::try_from(&HCV::<f64>::YELLOW).unwrap(),
+            URGB::YELLOW
+        );
+        assert_eq!(
+            URGB::<u8>::try_from(&HCV::<f64>::WHITE).unwrap(),
+            URGB::WHITE
+        );
+        assert_eq!(
+            URGB::<u8>::try_from(&HCV::<f64>::BLACK).unwrap(),
+            URGB::BLACK
+        );
     }
 
     #[test]
@@ -349,7 +693,7 @@ mod hcv_tests {
                     println!("[{}, {}, {}] -> {:?}", red, green, blue, rgb_in);
                     let hcv = HCV::<f32>::from(&rgb_in);
                     println!("{:?}", hcv);
-                    let rgb_out = RGB::<f32>::from(&hcv);
+                    let rgb_out = RGB::<f32>::try_from(&hcv).unwrap();
                     assert_approx_eq!(rgb_in, rgb_out, 0.000001);
                 }
             }
@@ -366,7 +710,7 @@ mod hcv_tests {
                     println!("[{}, {}, {}] -> {:?}", red, green, blue, rgb_in);
                     let hcv = HCV::<f64>::from(&rgb_in);
                     println!("{:?}", hcv);
-                    let rgb_out = RGB::<f64>::from(&hcv);
+                    let rgb_out = RGB::<f64>::try_from(&hcv).unwrap();
                     assert_approx_eq!(rgb_in, rgb_out, 0.000_000_000_000_001);
                 }
             }
@@ -383,7 +727,7 @@ mod hcv_tests {
                     println!("[{}, {}, {}] -> {:?}", red, green, blue, urgb_in);
                     let hcv = HCV::<f64>::from(&urgb_in);
                     println!("{:?}", hcv);
-                    let urgb_out = URGB::<u8>::from(&hcv);
+                    let urgb_out = URGB::<u8>::try_from(&hcv).unwrap();
                     assert_eq!(urgb_in, urgb_out);
                 }
             }
@@ -427,4 +771,182 @@ mod hcv_tests {
             }
         }
     }
+
+    #[test]
+    fn invalid_hcvs() {
+        let too_light = HCV::<f64> {
+            chroma: 1.0,
+            sum: 2.5,
+            ..HCV::RED
+        };
+        assert!(!too_light.is_valid());
+        assert!(matches!(
+            RGB::try_from(&too_light),
+            Err(HCVError::OutOfGamut(_))
+        ));
+        assert!(URGB::<u8>::try_from(&too_light).is_err());
+        let grey_with_chroma = HCV::<f64> {
+            chroma: 0.5,
+            ..HCV::WHITE
+        };
+        assert!(RGB::try_from(&grey_with_chroma).is_err());
+        for sum in [-0.5, 3.5, f64::NAN].iter() {
+            let hcv = HCV::<f64> {
+                sum: *sum,
+                ..HCV::RED
+            };
+            assert!(RGB::try_from(&hcv).is_err());
+            for mapping in [
+                HCVGamutMapping::ReduceChroma,
+                HCVGamutMapping::AdjustValue,
+                HCVGamutMapping::Nearest,
+            ]
+            .iter()
+            {
+                assert!(hcv.gamut_mapped(*mapping).is_valid());
+            }
+        }
+        assert_eq!(
+            grey_with_chroma.gamut_mapped(HCVGamutMapping::Nearest),
+            HCV::WHITE
+        );
+    }
+
+    #[test]
+    fn gamut_mapping() {
+        let too_light = HCV::<f64> {
+            chroma: 1.0,
+            sum: 2.5,
+            ..HCV::RED
+        };
+        let reduced = too_light.gamut_mapped(HCVGamutMapping::ReduceChroma);
+        assert_eq!(reduced.hue_data, too_light.hue_data);
+        assert_approx_eq!(reduced.chroma, 0.25);
+        assert_approx_eq!(reduced.sum, 2.5);
+        let adjusted = too_light.gamut_mapped(HCVGamutMapping::AdjustValue);
+        assert_eq!(adjusted, HCV::RED);
+        let nearest = too_light.gamut_mapped(HCVGamutMapping::Nearest);
+        assert!(nearest.is_valid());
+        assert_eq!(nearest.hue_data, too_light.hue_data);
+        let distance =
+            |hcv: &HCV<f64>| (hcv.chroma - too_light.chroma).hypot(hcv.value() - too_light.value());
+        assert!(distance(&nearest) < distance(&reduced));
+        assert!(distance(&nearest) < distance(&adjusted));
+        assert_approx_eq!(nearest.chroma, 10.0 / 13.0);
+        for mapping in [
+            HCVGamutMapping::ReduceChroma,
+            HCVGamutMapping::AdjustValue,
+            HCVGamutMapping::Nearest,
+        ]
+        .iter()
+        {
+            assert!(RGB::try_from(too_light.gamut_mapped(*mapping)).is_ok());
+            for rgb in [
+                RGB::<f64>::from([0.2, 0.4, 0.6]),
+                RGB::from([0.9, 0.1, 0.5]),
+                RGB::YELLOW,
+                RGB::WHITE,
+            ]
+            .iter()
+            {
+                let hcv = HCV::from(rgb);
+                assert_eq!(hcv.gamut_mapped(*mapping), hcv);
+                assert_eq!(hcv.rgb_with_mapping(*mapping), RGB::try_from(hcv).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn fallible_construction() {
+        let hcv = HCV::<f64>::try_new(Some(Degrees::DEG_0), 1.0, 1.0 / 3.0).unwrap();
+        assert_approx_eq!(RGB::try_from(hcv).unwrap(), RGB::RED);
+        assert_eq!(HCV::<f64>::try_new(None, 0.0, 0.5).unwrap().value(), 0.5);
+        assert!(matches!(
+            HCV::<f64>::try_new(Some(Degrees::DEG_0), 1.0, 0.9),
+            Err(ColourMathError::HCV(HCVError::OutOfGamut(_)))
+        ));
+        assert!(HCV::<f64>::try_new(None, 0.5, 0.5).is_err());
+    }
+
+    #[test]
+    fn colour_interface() {
+        let values = [0.0, 0.1, 0.5, 0.9, 1.0];
+        for red in values.iter() {
+            for green in values.iter() {
+                for blue in values.iter() {
+                    let rgb = RGB::<f64>::from([*red, *green, *blue]);
+                    let hcv = HCV::<f64>::from(rgb);
+                    assert_approx_eq!(ColourInterface::rgb(&hcv), rgb, 0.000_000_001);
+                    assert_eq!(ColourInterface::hcv(&hcv), hcv);
+                    assert_eq!(ColourInterface::is_grey(&hcv), rgb.is_grey());
+                    assert_approx_eq!(ColourInterface::chroma(&hcv), rgb.chroma());
+                    assert_approx_eq!(hcv.greyness(), rgb.greyness());
+                    assert_approx_eq!(ColourInterface::value(&hcv), rgb.value());
+                    assert_approx_eq!(hcv.warmth(), rgb.warmth(), 0.000_000_001);
+                    assert_approx_eq!(hcv.warmth_rgb(), rgb.warmth_rgb(), 0.000_000_001);
+                    assert_eq!(hcv.monochrome_rgb(), rgb.monochrome_rgb());
+                    assert_approx_eq!(hcv.max_chroma_rgb(), rgb.max_chroma_rgb(), 0.000_000_001);
+                    assert_eq!(hcv.best_foreground_rgb(), rgb.best_foreground_rgb());
+                    match ColourInterface::hue_angle(&hcv) {
+                        Some(angle) => {
+                            let expected = rgb.hue_angle().unwrap();
+                            assert_approx_eq!(angle, expected, 0.000_000_001);
+                            assert_approx_eq!(hcv.hue().unwrap().angle(), expected, 0.000_000_001);
+                        }
+                        None => assert!(rgb.hue_angle().is_none()),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn display_and_from_str() {
+        let hcv = HCV::<f64>::from_str("HCV(hue=30.0, chroma=0.5, value=0.6)").unwrap();
+        assert_approx_eq!(hcv.hue_angle().unwrap(), Degrees::from(30.0));
+        assert_approx_eq!(hcv.chroma(), 0.5);
+        assert_approx_eq!(hcv.value(), 0.6);
+        let reloaded = HCV::<f64>::from_str(&hcv.to_string()).unwrap();
+        assert_approx_eq!(reloaded.hue_angle().unwrap(), hcv.hue_angle().unwrap());
+        assert_eq!(reloaded.chroma(), hcv.chroma());
+        assert_eq!(reloaded.value(), hcv.value());
+        assert_eq!(
+            HCV::<f64>::RED.to_string(),
+            "HCV(hue=0.0, chroma=1.0, value=0.3333333333333333)"
+        );
+        let grey = HCV::<f64>::from_str("HCV(hue=None, chroma=0.0, value=0.25)").unwrap();
+        assert_eq!(grey, HCV::from(RGB::from([0.25, 0.25, 0.25])));
+        assert_eq!(grey.to_string(), "HCV(hue=None, chroma=0.0, value=0.25)");
+        assert_eq!(
+            HCV::<f64>::from_str(" HCV( hue = -120.0 , chroma = 1.0 , value = 0.333_3 ) "),
+            Err(HCVError::MalformedText(
+                " HCV( hue = -120.0 , chroma = 1.0 , value = 0.333_3 ) ".to_string()
+            ))
+        );
+        let blue = HCV::<f64>::from_str(" HCV( hue = -120.0 , chroma = 1.0 , value = 3.333e-1 ) ");
+        assert!(matches!(blue, Err(HCVError::OutOfGamut(_))));
+        let blue = HCV::<f64>::from_str("HCV(hue=-120.0, chroma=1.0, value=0.3333333333333333)");
+        assert_eq!(blue.unwrap(), HCV::BLUE);
+        for rgb in RGB::<f64>::PRIMARIES.iter().chain(RGB::SECONDARIES.iter()) {
+            let hcv = HCV::<f64>::from(rgb);
+            assert_eq!(HCV::from_str(&hcv.to_string()).unwrap(), hcv);
+        }
+        let hcv = HCV::<f32>::from(RGB::<f32>::from([0.1, 0.7, 0.3]));
+        assert_eq!(
+            HCV::<f32>::from_str(&hcv.to_string()).unwrap().to_string(),
+            hcv.to_string()
+        );
+        for bad in [
+            "HCV(hue=30.0, chroma=0.5)",
+            "HCV(hue=thirty, chroma=0.5, value=0.6)",
+            "HSV(hue=30.0, chroma=0.5, value=0.6)",
+        ]
+        .iter()
+        {
+            assert_eq!(
+                HCV::<f64>::from_str(bad),
+                Err(HCVError::MalformedText(bad.to_string()))
+            );
+        }
+    }
 }
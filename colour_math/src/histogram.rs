@@ -0,0 +1,251 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{ColourComponent, ColourInterface, ScalarAttribute, RGB};
+
+/// Counts of values falling into equal width bins covering a range.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Histogram<F: ColourComponent> {
+    range: (F, F),
+    counts: Vec<usize>,
+    excluded: usize,
+}
+
+impl<F: ColourComponent> Histogram<F> {
+    /// An empty histogram with `bins` bins covering `range` (`bins` must be at least one).
+    pub fn new(bins: usize, range: (F, F)) -> Self {
+        assert!(bins > 0, "a histogram needs at least one bin");
+        debug_assert!(range.0 < range.1, "range: {:?}", range);
+        Self {
+            range,
+            counts: vec![0; bins],
+            excluded: 0,
+        }
+    }
+
+    /// Count `value` in the appropriate bin.  Values outside the range are clamped into
+    /// the first or last bin.
+    pub fn add(&mut self, value: F) {
+        let bins = self.counts.len();
+        let position = (value - self.range.0) / (self.range.1 - self.range.0);
+        let index = (position * F::from(bins).unwrap())
+            .floor()
+            .max(F::ZERO)
+            .to_usize()
+            .unwrap_or(0)
+            .min(bins - 1);
+        self.counts[index] += 1;
+    }
+
+    /// Record a sample that has no value (e.g. the hue of a grey).
+    pub fn exclude(&mut self) {
+        self.excluded += 1;
+    }
+
+    pub fn range(&self) -> (F, F) {
+        self.range
+    }
+
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// The number of samples without a value (and therefore not counted in any bin).
+    pub fn excluded(&self) -> usize {
+        self.excluded
+    }
+
+    /// The number of samples counted in the bins.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn bin_width(&self) -> F {
+        (self.range.1 - self.range.0) / F::from(self.counts.len()).unwrap()
+    }
+
+    /// The value at the middle of the `index`th bin.
+    pub fn bin_centre(&self, index: usize) -> F {
+        self.range.0 + self.bin_width() * (F::from(index).unwrap() + F::HALF)
+    }
+
+    /// The index of the bin with the highest count (the first one if there's a tie).
+    pub fn mode_bin(&self) -> Option<usize> {
+        if self.total() == 0 {
+            return None;
+        }
+        let mut best = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            if *count > self.counts[best] {
+                best = index;
+            }
+        }
+        Some(best)
+    }
+}
+
+/// A histogram (with values in the range 0.0 to 1.0) of the given attribute of `pixels`.
+pub fn attribute_histogram<F: ColourComponent>(
+    pixels: &[RGB<F>],
+    attr: ScalarAttribute,
+    bins: usize,
+) -> Histogram<F> {
+    let mut histogram = Histogram::new(bins, (F::ZERO, F::ONE));
+    for pixel in pixels.iter() {
+        histogram.add(pixel.scalar_attribute(attr));
+    }
+    histogram
+}
+
+/// A histogram (with values in the range -180.0 to 180.0) of the hue angles of `pixels`.
+/// Grey pixels are counted as excluded.
+pub fn hue_angle_histogram<F: ColourComponent>(pixels: &[RGB<F>], bins: usize) -> Histogram<F> {
+    let mut histogram = Histogram::new(bins, (-F::DEG_180, F::DEG_180));
+    for pixel in pixels.iter() {
+        match pixel.hue_angle() {
+            Some(angle) => histogram.add(angle.degrees()),
+            None => histogram.exclude(),
+        }
+    }
+    histogram
+}
+
+/// Summary statistics of a set of values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution<F: ColourComponent> {
+    sorted: Vec<F>,
+    mean: F,
+}
+
+impl<F: ColourComponent> Distribution<F> {
+    /// Returns `None` if there are no values.
+    pub fn from_values(values: &[F]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).expect("values are not NaN"));
+        let mean = sorted.iter().copied().sum::<F>() / F::from(sorted.len()).unwrap();
+        Some(Self { sorted, mean })
+    }
+
+    pub fn count(&self) -> usize {
+        self.sorted.len()
+    }
+
+    pub fn min(&self) -> F {
+        self.sorted[0]
+    }
+
+    pub fn max(&self) -> F {
+        self.sorted[self.sorted.len() - 1]
+    }
+
+    pub fn mean(&self) -> F {
+        self.mean
+    }
+
+    /// The population variance.
+    pub fn variance(&self) -> F {
+        let sum_of_squares = self
+            .sorted
+            .iter()
+            .fold(F::ZERO, |acc, v| acc + (*v - self.mean) * (*v - self.mean));
+        sum_of_squares / F::from(self.sorted.len()).unwrap()
+    }
+
+    pub fn standard_deviation(&self) -> F {
+        self.variance().sqrt()
+    }
+
+    /// The value below which `percent` percent of the values fall (interpolating
+    /// linearly between the closest ranks).
+    pub fn percentile(&self, percent: F) -> F {
+        debug_assert!(percent >= F::ZERO && percent <= F::from(100.0).unwrap());
+        let rank = percent / F::from(100.0).unwrap() * F::from(self.sorted.len() - 1).unwrap();
+        let lower = rank.floor().to_usize().unwrap();
+        let upper = rank.ceil().to_usize().unwrap();
+        let weight = rank - rank.floor();
+        self.sorted[lower] * (F::ONE - weight) + self.sorted[upper] * weight
+    }
+
+    pub fn median(&self) -> F {
+        self.percentile(F::from(50.0).unwrap())
+    }
+}
+
+/// The distribution of the given attribute of `pixels` (`None` if there are none).
+pub fn attribute_distribution<F: ColourComponent>(
+    pixels: &[RGB<F>],
+    attr: ScalarAttribute,
+) -> Option<Distribution<F>> {
+    let values: Vec<F> = pixels.iter().map(|p| p.scalar_attribute(attr)).collect();
+    Distribution::from_values(&values)
+}
+
+#[cfg(test)]
+mod histogram_tests {
+    use super::*;
+    use crate::{
+        image::{GenericImage, OpaqueImage},
+        HueConstants, RGBConstants,
+    };
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn histogram_bins() {
+        let mut histogram = Histogram::<f64>::new(4, (0.0, 1.0));
+        for value in [0.0, 0.1, 0.25, 0.5, 0.99, 1.0, 1.5, -0.5].iter() {
+            histogram.add(*value);
+        }
+        assert_eq!(histogram.counts(), &[3, 1, 1, 3]);
+        assert_eq!(histogram.total(), 8);
+        assert_eq!(histogram.excluded(), 0);
+        assert_approx_eq!(histogram.bin_width(), 0.25);
+        assert_approx_eq!(histogram.bin_centre(1), 0.375);
+        assert_eq!(histogram.mode_bin(), Some(0));
+        assert_eq!(Histogram::<f64>::new(3, (0.0, 1.0)).mode_bin(), None);
+    }
+
+    #[test]
+    fn image_histograms() {
+        let pixels = vec![
+            RGB::<f64>::RED,
+            RGB::GREEN,
+            RGB::BLUE,
+            RGB::WHITE,
+            RGB::BLACK,
+            RGB::from([0.5, 0.5, 0.5]),
+        ];
+        let image = OpaqueImage::from((pixels.clone(), 3));
+        let histogram = image.attribute_histogram(ScalarAttribute::Chroma, 2);
+        assert_eq!(histogram.counts(), &[3, 3]);
+        let histogram = image.attribute_histogram(ScalarAttribute::Value, 3);
+        assert_eq!(histogram.counts(), &[1, 4, 1]);
+        let histogram = image.hue_angle_histogram(6);
+        assert_eq!(histogram.excluded(), 3);
+        assert_eq!(histogram.total(), 3);
+        let generic_image = GenericImage::from((pixels, 3));
+        assert_eq!(generic_image.hue_angle_histogram(6), histogram);
+    }
+
+    #[test]
+    fn distributions() {
+        assert!(Distribution::<f64>::from_values(&[]).is_none());
+        let distribution = Distribution::from_values(&[0.4, 0.1, 0.3, 0.2]).unwrap();
+        assert_eq!(distribution.count(), 4);
+        assert_eq!(distribution.min(), 0.1);
+        assert_eq!(distribution.max(), 0.4);
+        assert_approx_eq!(distribution.mean(), 0.25);
+        assert_approx_eq!(distribution.median(), 0.25);
+        assert_approx_eq!(distribution.variance(), 0.0125);
+        assert_approx_eq!(distribution.percentile(0.0), 0.1);
+        assert_approx_eq!(distribution.percentile(100.0), 0.4);
+        assert_approx_eq!(distribution.percentile(75.0), 0.325);
+        let image = OpaqueImage::from((vec![RGB::<f64>::RED, RGB::YELLOW, RGB::WHITE], 3));
+        let distribution = image
+            .attribute_distribution(ScalarAttribute::Value)
+            .unwrap();
+        assert_approx_eq!(distribution.median(), 2.0 / 3.0);
+        assert_approx_eq!(distribution.min(), 1.0 / 3.0);
+    }
+}
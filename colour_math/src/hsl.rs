@@ -0,0 +1,333 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use num_traits_plus::float_plus::*;
+
+use crate::{
+    chroma::HueData,
+    hsv::{
+        hcv_for_hue_data_max_min, hcv_hue_data_max_min, hexagonal_hue_angle,
+        hue_data_for_hexagonal_angle, rgb_for_hue_data_max_min, rgb_hue_data_max_min,
+    },
+    ColourComponent, Degrees, HueConstants, RGBConstants, HCV, RGB,
+};
+
+/// The chroma (as per `ColourInterface::chroma()`) of a colour with the given HSL
+/// saturation and lightness.  HSL lightness is the mean of the largest and smallest RGB
+/// components so the largest possible chroma for a lightness is `1 - |2L - 1|`.
+pub fn chroma_for_hsl<F: ColourComponent>(saturation: F, lightness: F) -> F {
+    debug_assert!(saturation.is_proportion() && lightness.is_proportion());
+    (F::ONE - (F::TWO * lightness - F::ONE).abs()) * saturation
+}
+
+/// The greyness (as per `ColourInterface::greyness()`) of a colour with the given HSL
+/// saturation and lightness i.e. one minus its chroma.
+pub fn greyness_for_hsl<F: ColourComponent>(saturation: F, lightness: F) -> F {
+    F::ONE - chroma_for_hsl(saturation, lightness)
+}
+
+/// The HSL saturation of a colour with the given chroma (as per `ColourInterface::chroma()`)
+/// and HSL lightness.  Black and white have a saturation of zero.
+pub fn hsl_saturation_for_chroma<F: ColourComponent>(chroma: F, lightness: F) -> F {
+    let max_chroma = F::ONE - (F::TWO * lightness - F::ONE).abs();
+    debug_assert!(chroma <= max_chroma + F::from(1e-12).unwrap());
+    if max_chroma > F::ZERO {
+        (chroma / max_chroma).min(F::ONE)
+    } else {
+        F::ZERO
+    }
+}
+
+/// Hue, saturation and lightness as used by many external applications (and CSS).
+/// The hue angle is the conventional (hexagonal) one and differs from the geometric
+/// hue angle of `HCV`.  The hue of a grey is reported as zero.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct HSL<F: ColourComponent> {
+    pub(crate) hue: Degrees<F>,
+    pub(crate) saturation: F,
+    pub(crate) lightness: F,
+}
+
+impl<F: ColourComponent> HSL<F> {
+    pub fn h(&self) -> Degrees<F> {
+        self.hue
+    }
+
+    pub fn s(&self) -> F {
+        self.saturation
+    }
+
+    pub fn l(&self) -> F {
+        self.lightness
+    }
+
+    fn hue_data(&self) -> Option<HueData<F>> {
+        if chroma_for_hsl(self.saturation, self.lightness) > F::ZERO {
+            Some(hue_data_for_hexagonal_angle(self.hue))
+        } else {
+            None
+        }
+    }
+
+    fn max_min(&self) -> (F, F) {
+        let half_chroma = chroma_for_hsl(self.saturation, self.lightness) / F::TWO;
+        (
+            (self.lightness + half_chroma).min(F::ONE),
+            (self.lightness - half_chroma).max(F::ZERO),
+        )
+    }
+}
+
+impl<F: ColourComponent> HueConstants for HSL<F> {
+    const RED: Self = Self {
+        hue: Degrees::RED,
+        saturation: F::ONE,
+        lightness: F::HALF,
+    };
+    const GREEN: Self = Self {
+        hue: Degrees::GREEN,
+        saturation: F::ONE,
+        lightness: F::HALF,
+    };
+    const BLUE: Self = Self {
+        hue: Degrees::BLUE,
+        saturation: F::ONE,
+        lightness: F::HALF,
+    };
+
+    const CYAN: Self = Self {
+        hue: Degrees::CYAN,
+        saturation: F::ONE,
+        lightness: F::HALF,
+    };
+    const MAGENTA: Self = Self {
+        hue: Degrees::MAGENTA,
+        saturation: F::ONE,
+        lightness: F::HALF,
+    };
+    const YELLOW: Self = Self {
+        hue: Degrees::YELLOW,
+        saturation: F::ONE,
+        lightness: F::HALF,
+    };
+}
+
+impl<F: ColourComponent> RGBConstants for HSL<F> {
+    const WHITE: Self = Self {
+        hue: Degrees::DEG_0,
+        saturation: F::ZERO,
+        lightness: F::ONE,
+    };
+    const BLACK: Self = Self {
+        hue: Degrees::DEG_0,
+        saturation: F::ZERO,
+        lightness: F::ZERO,
+    };
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for HSL<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.hue.approx_eq(&other.hue, max_diff)
+            && self.saturation.approx_eq(&other.saturation, max_diff)
+            && self.lightness.approx_eq(&other.lightness, max_diff)
+    }
+}
+
+/// Create from an array of the form [hue degrees, saturation, lightness].
+impl<F: ColourComponent> From<[F; 3]> for HSL<F> {
+    fn from(array: [F; 3]) -> Self {
+        debug_assert!(array[1].is_proportion() && array[2].is_proportion());
+        Self {
+            hue: Degrees::from(array[0]),
+            saturation: array[1],
+            lightness: array[2],
+        }
+    }
+}
+
+/// An array of the form [hue degrees (0.0 to 360.0), saturation, lightness].
+impl<F: ColourComponent> From<&HSL<F>> for [F; 3] {
+    fn from(hsl: &HSL<F>) -> Self {
+        let mut degrees = hsl.hue.degrees();
+        if degrees < F::ZERO {
+            degrees += F::DEG_360;
+        }
+        [degrees, hsl.saturation, hsl.lightness]
+    }
+}
+
+impl<F: ColourComponent> From<(Option<HueData<F>>, F, F)> for HSL<F> {
+    fn from(hue_data_max_min: (Option<HueData<F>>, F, F)) -> Self {
+        match hue_data_max_min {
+            (Some(hue_data), max, min) if max > min => {
+                let lightness = (max + min) / F::TWO;
+                Self {
+                    hue: hexagonal_hue_angle(&hue_data),
+                    saturation: hsl_saturation_for_chroma(max - min, lightness),
+                    lightness,
+                }
+            }
+            (_, max, _) => Self {
+                hue: Degrees::DEG_0,
+                saturation: F::ZERO,
+                lightness: max,
+            },
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for HSL<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        rgb_hue_data_max_min(rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for HSL<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&HSL<F>> for RGB<F> {
+    fn from(hsl: &HSL<F>) -> Self {
+        let (max, min) = hsl.max_min();
+        match hsl.hue_data() {
+            Some(hue_data) => rgb_for_hue_data_max_min(&hue_data, max, min),
+            None => [hsl.lightness, hsl.lightness, hsl.lightness].into(),
+        }
+    }
+}
+
+impl<F: ColourComponent> From<HSL<F>> for RGB<F> {
+    fn from(hsl: HSL<F>) -> Self {
+        (&hsl).into()
+    }
+}
+
+impl<F: ColourComponent> From<&HCV<F>> for HSL<F> {
+    fn from(hcv: &HCV<F>) -> Self {
+        hcv_hue_data_max_min(hcv).into()
+    }
+}
+
+impl<F: ColourComponent> From<HCV<F>> for HSL<F> {
+    fn from(hcv: HCV<F>) -> Self {
+        (&hcv).into()
+    }
+}
+
+impl<F: ColourComponent> From<&HSL<F>> for HCV<F> {
+    fn from(hsl: &HSL<F>) -> Self {
+        let (max, min) = hsl.max_min();
+        hcv_for_hue_data_max_min(hsl.hue_data(), max, min)
+    }
+}
+
+impl<F: ColourComponent> From<HSL<F>> for HCV<F> {
+    fn from(hsl: HSL<F>) -> Self {
+        (&hsl).into()
+    }
+}
+
+impl_colour_interface_via_rgb!(HSL, {
+    fn hsl(&self) -> HSL<F> {
+        *self
+    }
+});
+
+#[cfg(test)]
+mod hsl_tests {
+    use super::*;
+    use crate::{ColourInterface, HSV};
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn constants_match_conversions() {
+        for (rgb, hsl) in RGB::<f64>::PRIMARIES
+            .iter()
+            .zip(HSL::<f64>::PRIMARIES.iter())
+        {
+            assert_eq!(HSL::from(rgb), *hsl);
+            assert_eq!(RGB::from(hsl), *rgb);
+        }
+        for (rgb, hsl) in RGB::<f64>::SECONDARIES
+            .iter()
+            .zip(HSL::<f64>::SECONDARIES.iter())
+        {
+            assert_eq!(HSL::from(rgb), *hsl);
+            assert_eq!(RGB::from(hsl), *rgb);
+        }
+        for (rgb, hsl) in RGB::<f32>::GREYS.iter().zip(HSL::<f32>::GREYS.iter()) {
+            assert_eq!(HSL::from(rgb), *hsl);
+            assert_eq!(RGB::from(hsl), *rgb);
+        }
+        for (hcv, hsl) in HCV::<f64>::PRIMARIES
+            .iter()
+            .zip(HSL::<f64>::PRIMARIES.iter())
+        {
+            assert_eq!(HSL::from(hcv), *hsl);
+            assert_eq!(HCV::from(hsl), *hcv);
+        }
+    }
+
+    #[test]
+    fn conventional_values() {
+        let hsl = HSL::<f64>::from(RGB::from([0.8, 0.4, 0.2]));
+        assert_approx_eq!(<[f64; 3]>::from(&hsl)[0], 20.0, 0.000_000_001);
+        assert_approx_eq!(hsl.s(), 0.6, 0.000_000_001);
+        assert_approx_eq!(hsl.l(), 0.5);
+        let hsl = HSL::<f64>::from([210.0, 0.5, 0.6]);
+        assert_approx_eq!(RGB::from(hsl), RGB::from([0.4, 0.6, 0.8]), 0.000_000_001);
+    }
+
+    #[test]
+    fn rgb_and_hcv_round_trips() {
+        let values = [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+        for red in values.iter() {
+            for green in values.iter() {
+                for blue in values.iter() {
+                    let rgb = RGB::<f64>::from([*red, *green, *blue]);
+                    let hsl = HSL::from(rgb);
+                    assert_approx_eq!(RGB::from(hsl), rgb, 0.000_000_001);
+                    assert_approx_eq!(hsl.h(), HSV::from(rgb).h(), 0.000_000_001);
+                    let hcv = HCV::from(rgb);
+                    assert_approx_eq!(HSL::from(hcv), hsl, 0.000_000_001);
+                    let hcv_from_hsl = HCV::from(hsl);
+                    assert_eq!(hcv_from_hsl.hue_data.is_some(), hcv.hue_data.is_some());
+                    assert_approx_eq!(hcv_from_hsl.chroma(), hcv.chroma(), 0.000_000_001);
+                    assert_approx_eq!(hcv_from_hsl.value(), hcv.value(), 0.000_000_001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn saturation_and_chroma() {
+        for array in [
+            [0.8, 0.4, 0.2],
+            [0.1, 0.5, 0.3],
+            [0.6, 0.6, 0.6],
+            [0.0, 0.0, 0.9],
+        ]
+        .iter()
+        {
+            let rgb = RGB::<f64>::from(*array);
+            let hsl = rgb.hsl();
+            assert_approx_eq!(
+                chroma_for_hsl(hsl.s(), hsl.l()),
+                rgb.chroma(),
+                0.000_000_001
+            );
+            assert_approx_eq!(
+                greyness_for_hsl(hsl.s(), hsl.l()),
+                rgb.greyness(),
+                0.000_000_001
+            );
+            assert_approx_eq!(
+                hsl_saturation_for_chroma(rgb.chroma(), hsl.l()),
+                hsl.s(),
+                0.000_000_001
+            );
+        }
+        assert_eq!(hsl_saturation_for_chroma(0.0_f64, 1.0), 0.0);
+    }
+}
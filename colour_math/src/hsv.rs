@@ -0,0 +1,429 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use num_traits_plus::float_plus::*;
+
+use crate::{
+    chroma::HueData, ColourComponent, Degrees, HueConstants, IndicesValueOrder, RGBConstants, HCV,
+    RGB,
+};
+
+// NB: the hue angles used by HSV and HSL are hexagonal (i.e. piecewise
+// linear between the primaries and secondaries) rather than the geometric
+// angles used by HCV so conversions go via the HueData.
+
+pub(crate) fn hexagonal_hue_angle<F: ColourComponent>(hue_data: &HueData<F>) -> Degrees<F> {
+    let offset = Degrees::<F>::from(Degrees::<F>::DEG_60.degrees() * hue_data.second);
+    match hue_data.io {
+        IndicesValueOrder::RED => -offset,
+        IndicesValueOrder::YELLOW => offset,
+        IndicesValueOrder::GREEN => Degrees::GREEN - offset,
+        IndicesValueOrder::CYAN => Degrees::GREEN + offset,
+        IndicesValueOrder::BLUE => Degrees::BLUE - offset,
+        IndicesValueOrder::MAGENTA => Degrees::BLUE + offset,
+        _ => panic!("illegal colour component indices: {:?}", hue_data.io),
+    }
+}
+
+pub(crate) fn hue_data_for_hexagonal_angle<F: ColourComponent>(angle: Degrees<F>) -> HueData<F> {
+    let sixty = Degrees::<F>::DEG_60.degrees();
+    let mut degrees = angle.degrees();
+    if degrees < F::ZERO {
+        degrees += F::DEG_360;
+    }
+    let sector = (degrees / sixty).floor();
+    let fraction = degrees / sixty - sector;
+    let (io, second) = match sector.to_u8() {
+        Some(0) if fraction == F::ZERO => (IndicesValueOrder::RED, F::ZERO),
+        Some(0) => (IndicesValueOrder::YELLOW, fraction),
+        Some(1) if fraction == F::ZERO => (IndicesValueOrder::YELLOW, F::ONE),
+        Some(1) => (IndicesValueOrder::GREEN, F::ONE - fraction),
+        Some(2) if fraction == F::ZERO => (IndicesValueOrder::GREEN, F::ZERO),
+        Some(2) => (IndicesValueOrder::CYAN, fraction),
+        Some(3) if fraction == F::ZERO => (IndicesValueOrder::CYAN, F::ONE),
+        Some(3) => (IndicesValueOrder::BLUE, F::ONE - fraction),
+        Some(4) if fraction == F::ZERO => (IndicesValueOrder::BLUE, F::ZERO),
+        Some(4) => (IndicesValueOrder::MAGENTA, fraction),
+        Some(5) if fraction == F::ZERO => (IndicesValueOrder::MAGENTA, F::ONE),
+        _ => (IndicesValueOrder::RED, F::ONE - fraction),
+    };
+    HueData { io, second }
+}
+
+// The RGB with the given hue, maximum and minimum component values.
+pub(crate) fn rgb_for_hue_data_max_min<F: ColourComponent>(
+    hue_data: &HueData<F>,
+    max: F,
+    min: F,
+) -> RGB<F> {
+    let mut array = [min, min, min];
+    array[hue_data.io[0] as usize] = max;
+    array[hue_data.io[1] as usize] = (min + hue_data.second * (max - min)).min(max);
+    array.into()
+}
+
+// The hue, maximum and minimum component values of an HCV.
+pub(crate) fn hcv_hue_data_max_min<F: ColourComponent>(hcv: &HCV<F>) -> (Option<HueData<F>>, F, F) {
+    if let Some(hue_data) = hcv.hue_data {
+        let min = ((hcv.sum - hcv.chroma * (F::ONE + hue_data.second)) / F::THREE).max(F::ZERO);
+        (Some(hue_data), (min + hcv.chroma).min(F::ONE), min)
+    } else {
+        let value = hcv.sum / F::THREE;
+        (None, value, value)
+    }
+}
+
+// The hue, maximum and minimum component values of an RGB.
+pub(crate) fn rgb_hue_data_max_min<F: ColourComponent>(rgb: &RGB<F>) -> (Option<HueData<F>>, F, F) {
+    if let Some(io) = rgb.indices_value_order() {
+        let max = rgb[io[0]];
+        let min = rgb[io[2]];
+        let second = (rgb[io[1]] - min) / (max - min);
+        (Some(HueData { io, second }), max, min)
+    } else {
+        (None, rgb[crate::CCI::Red], rgb[crate::CCI::Red])
+    }
+}
+
+// HCV from the hue, maximum and minimum component values.
+pub(crate) fn hcv_for_hue_data_max_min<F: ColourComponent>(
+    hue_data: Option<HueData<F>>,
+    max: F,
+    min: F,
+) -> HCV<F> {
+    match hue_data {
+        Some(hue_data) if max > min => {
+            let chroma = max - min;
+            HCV {
+                hue_data: Some(hue_data),
+                chroma,
+                sum: (min * F::THREE + chroma * (F::ONE + hue_data.second)).min(F::THREE),
+            }
+        }
+        _ => HCV {
+            hue_data: None,
+            chroma: F::ZERO,
+            sum: max * F::THREE,
+        },
+    }
+}
+
+/// The chroma (as per `ColourInterface::chroma()`) of a colour with the given HSV
+/// saturation and value.  As HSV value is the largest RGB component and chroma is the
+/// difference between the largest and smallest RGB components this is just their product.
+pub fn chroma_for_hsv<F: ColourComponent>(saturation: F, value: F) -> F {
+    debug_assert!(saturation.is_proportion() && value.is_proportion());
+    saturation * value
+}
+
+/// The greyness (as per `ColourInterface::greyness()`) of a colour with the given HSV
+/// saturation and value i.e. one minus its chroma.
+pub fn greyness_for_hsv<F: ColourComponent>(saturation: F, value: F) -> F {
+    F::ONE - chroma_for_hsv(saturation, value)
+}
+
+/// The HSV saturation of a colour with the given chroma (as per `ColourInterface::chroma()`)
+/// and HSV value.  Black has a saturation of zero.
+pub fn hsv_saturation_for_chroma<F: ColourComponent>(chroma: F, value: F) -> F {
+    debug_assert!(
+        chroma <= value + F::from(1e-12).unwrap(),
+        "chroma: {:?} value: {:?}",
+        chroma,
+        value
+    );
+    if value == F::ZERO {
+        F::ZERO
+    } else {
+        (chroma / value).min(F::ONE)
+    }
+}
+
+/// Hue, saturation and value as used by many external applications.  The hue angle is
+/// the conventional (hexagonal) one and differs from the geometric hue angle of `HCV`.
+/// The hue of a grey is reported as zero.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct HSV<F: ColourComponent> {
+    pub(crate) hue: Degrees<F>,
+    pub(crate) saturation: F,
+    pub(crate) value: F,
+}
+
+impl<F: ColourComponent> HSV<F> {
+    pub fn h(&self) -> Degrees<F> {
+        self.hue
+    }
+
+    pub fn s(&self) -> F {
+        self.saturation
+    }
+
+    pub fn v(&self) -> F {
+        self.value
+    }
+
+    fn hue_data(&self) -> Option<HueData<F>> {
+        if self.saturation > F::ZERO && self.value > F::ZERO {
+            Some(hue_data_for_hexagonal_angle(self.hue))
+        } else {
+            None
+        }
+    }
+
+    fn max_min(&self) -> (F, F) {
+        (
+            self.value,
+            self.value - chroma_for_hsv(self.saturation, self.value),
+        )
+    }
+}
+
+impl<F: ColourComponent> HueConstants for HSV<F> {
+    const RED: Self = Self {
+        hue: Degrees::RED,
+        saturation: F::ONE,
+        value: F::ONE,
+    };
+    const GREEN: Self = Self {
+        hue: Degrees::GREEN,
+        saturation: F::ONE,
+        value: F::ONE,
+    };
+    const BLUE: Self = Self {
+        hue: Degrees::BLUE,
+        saturation: F::ONE,
+        value: F::ONE,
+    };
+
+    const CYAN: Self = Self {
+        hue: Degrees::CYAN,
+        saturation: F::ONE,
+        value: F::ONE,
+    };
+    const MAGENTA: Self = Self {
+        hue: Degrees::MAGENTA,
+        saturation: F::ONE,
+        value: F::ONE,
+    };
+    const YELLOW: Self = Self {
+        hue: Degrees::YELLOW,
+        saturation: F::ONE,
+        value: F::ONE,
+    };
+}
+
+impl<F: ColourComponent> RGBConstants for HSV<F> {
+    const WHITE: Self = Self {
+        hue: Degrees::DEG_0,
+        saturation: F::ZERO,
+        value: F::ONE,
+    };
+    const BLACK: Self = Self {
+        hue: Degrees::DEG_0,
+        saturation: F::ZERO,
+        value: F::ZERO,
+    };
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for HSV<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.hue.approx_eq(&other.hue, max_diff)
+            && self.saturation.approx_eq(&other.saturation, max_diff)
+            && self.value.approx_eq(&other.value, max_diff)
+    }
+}
+
+/// Create from an array of the form [hue degrees, saturation, value].
+impl<F: ColourComponent> From<[F; 3]> for HSV<F> {
+    fn from(array: [F; 3]) -> Self {
+        debug_assert!(array[1].is_proportion() && array[2].is_proportion());
+        Self {
+            hue: Degrees::from(array[0]),
+            saturation: array[1],
+            value: array[2],
+        }
+    }
+}
+
+/// An array of the form [hue degrees (0.0 to 360.0), saturation, value].
+impl<F: ColourComponent> From<&HSV<F>> for [F; 3] {
+    fn from(hsv: &HSV<F>) -> Self {
+        let mut degrees = hsv.hue.degrees();
+        if degrees < F::ZERO {
+            degrees += F::DEG_360;
+        }
+        [degrees, hsv.saturation, hsv.value]
+    }
+}
+
+impl<F: ColourComponent> From<(Option<HueData<F>>, F, F)> for HSV<F> {
+    fn from(hue_data_max_min: (Option<HueData<F>>, F, F)) -> Self {
+        match hue_data_max_min {
+            (Some(hue_data), max, min) if max > min => Self {
+                hue: hexagonal_hue_angle(&hue_data),
+                saturation: hsv_saturation_for_chroma(max - min, max),
+                value: max,
+            },
+            (_, max, _) => Self {
+                hue: Degrees::DEG_0,
+                saturation: F::ZERO,
+                value: max,
+            },
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for HSV<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        rgb_hue_data_max_min(rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for HSV<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&HSV<F>> for RGB<F> {
+    fn from(hsv: &HSV<F>) -> Self {
+        let (max, min) = hsv.max_min();
+        match hsv.hue_data() {
+            Some(hue_data) => rgb_for_hue_data_max_min(&hue_data, max, min),
+            None => [max, max, max].into(),
+        }
+    }
+}
+
+impl<F: ColourComponent> From<HSV<F>> for RGB<F> {
+    fn from(hsv: HSV<F>) -> Self {
+        (&hsv).into()
+    }
+}
+
+impl<F: ColourComponent> From<&HCV<F>> for HSV<F> {
+    fn from(hcv: &HCV<F>) -> Self {
+        hcv_hue_data_max_min(hcv).into()
+    }
+}
+
+impl<F: ColourComponent> From<HCV<F>> for HSV<F> {
+    fn from(hcv: HCV<F>) -> Self {
+        (&hcv).into()
+    }
+}
+
+impl<F: ColourComponent> From<&HSV<F>> for HCV<F> {
+    fn from(hsv: &HSV<F>) -> Self {
+        let (max, min) = hsv.max_min();
+        hcv_for_hue_data_max_min(hsv.hue_data(), max, min)
+    }
+}
+
+impl<F: ColourComponent> From<HSV<F>> for HCV<F> {
+    fn from(hsv: HSV<F>) -> Self {
+        (&hsv).into()
+    }
+}
+
+impl_colour_interface_via_rgb!(HSV, {
+    fn hsv(&self) -> HSV<F> {
+        *self
+    }
+});
+
+#[cfg(test)]
+mod hsv_tests {
+    use super::*;
+    use crate::ColourInterface;
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn constants_match_conversions() {
+        for (rgb, hsv) in RGB::<f64>::PRIMARIES
+            .iter()
+            .zip(HSV::<f64>::PRIMARIES.iter())
+        {
+            assert_eq!(HSV::from(rgb), *hsv);
+            assert_eq!(RGB::from(hsv), *rgb);
+        }
+        for (rgb, hsv) in RGB::<f64>::SECONDARIES
+            .iter()
+            .zip(HSV::<f64>::SECONDARIES.iter())
+        {
+            assert_eq!(HSV::from(rgb), *hsv);
+            assert_eq!(RGB::from(hsv), *rgb);
+        }
+        for (rgb, hsv) in RGB::<f32>::GREYS.iter().zip(HSV::<f32>::GREYS.iter()) {
+            assert_eq!(HSV::from(rgb), *hsv);
+            assert_eq!(RGB::from(hsv), *rgb);
+        }
+        for (hcv, hsv) in HCV::<f64>::SECONDARIES
+            .iter()
+            .zip(HSV::<f64>::SECONDARIES.iter())
+        {
+            assert_eq!(HSV::from(hcv), *hsv);
+            assert_eq!(HCV::from(hsv), *hcv);
+        }
+    }
+
+    #[test]
+    fn conventional_values() {
+        let hsv = HSV::<f64>::from(RGB::from([0.8, 0.4, 0.2]));
+        assert_approx_eq!(<[f64; 3]>::from(&hsv)[0], 20.0, 0.000_000_001);
+        assert_approx_eq!(hsv.s(), 0.75);
+        assert_approx_eq!(hsv.v(), 0.8);
+        let hsv = HSV::<f64>::from([210.0, 0.5, 0.6]);
+        assert_approx_eq!(RGB::from(hsv), RGB::from([0.3, 0.45, 0.6]), 0.000_000_001);
+    }
+
+    #[test]
+    fn rgb_and_hcv_round_trips() {
+        let values = [0.0, 0.1, 0.25, 0.5, 0.75, 0.9, 1.0];
+        for red in values.iter() {
+            for green in values.iter() {
+                for blue in values.iter() {
+                    let rgb = RGB::<f64>::from([*red, *green, *blue]);
+                    let hsv = HSV::from(rgb);
+                    assert_approx_eq!(RGB::from(hsv), rgb, 0.000_000_001);
+                    let hcv = HCV::from(rgb);
+                    assert_approx_eq!(HSV::from(hcv), hsv, 0.000_000_001);
+                    let hcv_from_hsv = HCV::from(hsv);
+                    assert_eq!(hcv_from_hsv.hue_data.is_some(), hcv.hue_data.is_some());
+                    assert_approx_eq!(hcv_from_hsv.chroma(), hcv.chroma(), 0.000_000_001);
+                    assert_approx_eq!(hcv_from_hsv.value(), hcv.value(), 0.000_000_001);
+                    assert_approx_eq!(HSV::from(hcv_from_hsv), hsv, 0.000_000_001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn saturation_and_chroma() {
+        for array in [
+            [0.8, 0.4, 0.2],
+            [0.1, 0.5, 0.3],
+            [0.6, 0.6, 0.6],
+            [0.0, 0.0, 0.9],
+        ]
+        .iter()
+        {
+            let rgb = RGB::<f64>::from(*array);
+            let hsv = rgb.hsv();
+            assert_approx_eq!(
+                chroma_for_hsv(hsv.s(), hsv.v()),
+                rgb.chroma(),
+                0.000_000_001
+            );
+            assert_approx_eq!(
+                greyness_for_hsv(hsv.s(), hsv.v()),
+                rgb.greyness(),
+                0.000_000_001
+            );
+            assert_approx_eq!(
+                hsv_saturation_for_chroma(rgb.chroma(), hsv.v()),
+                hsv.s(),
+                0.000_000_001
+            );
+        }
+        assert_eq!(hsv_saturation_for_chroma(0.0_f64, 0.0), 0.0);
+    }
+}
@@ -0,0 +1,294 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{
+    cie::{apply_matrix, D65},
+    ColourComponent, XYZ,
+};
+
+/// CIE standard illuminants (and the reference white points that they define).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Illuminant {
+    /// Incandescent/tungsten (2856 K)
+    A,
+    /// Horizon light (5003 K) as used by ICC profiles and for viewing prints
+    D50,
+    /// Mid-morning/mid-afternoon daylight (5503 K)
+    D55,
+    /// Noon daylight (6504 K) and the white point of sRGB
+    D65,
+    /// North sky daylight (7504 K)
+    D75,
+    /// Equal energy
+    E,
+    /// Cool white fluorescent (4230 K)
+    F2,
+    /// Broad-band daylight fluorescent (6500 K)
+    F7,
+    /// Narrow tri-band fluorescent (4000 K)
+    F11,
+}
+
+impl Default for Illuminant {
+    fn default() -> Self {
+        Illuminant::D65
+    }
+}
+
+impl Illuminant {
+    pub const ALL: [Illuminant; 9] = [
+        Illuminant::A,
+        Illuminant::D50,
+        Illuminant::D55,
+        Illuminant::D65,
+        Illuminant::D75,
+        Illuminant::E,
+        Illuminant::F2,
+        Illuminant::F7,
+        Illuminant::F11,
+    ];
+
+    /// The CIE 1931 (2° observer) xy chromaticity coordinates of the white point.
+    /// NB: D65 uses the values from the sRGB specification.
+    pub fn chromaticity(&self) -> (f64, f64) {
+        match self {
+            Illuminant::A => (0.447_57, 0.407_45),
+            Illuminant::D50 => (0.345_67, 0.358_50),
+            Illuminant::D55 => (0.332_42, 0.347_43),
+            Illuminant::D65 => (0.312_7, 0.329_0),
+            Illuminant::D75 => (0.299_02, 0.314_85),
+            Illuminant::E => (1.0 / 3.0, 1.0 / 3.0),
+            Illuminant::F2 => (0.372_08, 0.375_29),
+            Illuminant::F7 => (0.312_92, 0.329_33),
+            Illuminant::F11 => (0.380_52, 0.377_13),
+        }
+    }
+
+    pub(crate) fn white_point_array(&self) -> [f64; 3] {
+        match self {
+            Illuminant::D65 => D65,
+            _ => {
+                let (x, y) = self.chromaticity();
+                [x / y, 1.0, (1.0 - x - y) / y]
+            }
+        }
+    }
+
+    /// The XYZ coordinates of the white point (with Y normalised to 1).
+    pub fn white_point<F: ColourComponent>(&self) -> XYZ<F> {
+        let [x, y, z] = self.white_point_array();
+        [
+            F::from(x).unwrap(),
+            F::from(y).unwrap(),
+            F::from(z).unwrap(),
+        ]
+        .into()
+    }
+}
+
+const BRADFORD: [[f64; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+const BRADFORD_INVERSE: [[f64; 3]; 3] = [
+    [
+        0.986_992_905_466_712_1,
+        -0.147_054_256_420_990_1,
+        0.159_962_651_663_731_25,
+    ],
+    [
+        0.432_305_269_723_394_5,
+        0.518_360_271_536_777_6,
+        0.049_291_228_212_855_615,
+    ],
+    [
+        -0.008_528_664_575_177_331,
+        0.040_042_821_654_084_86,
+        0.968_486_695_787_55,
+    ],
+];
+
+// Hunt-Pointer-Estevez cone responses normalised to D65
+const VON_KRIES: [[f64; 3]; 3] = [
+    [0.400_24, 0.707_60, -0.080_81],
+    [-0.226_30, 1.165_32, 0.045_70],
+    [0.0, 0.0, 0.918_22],
+];
+
+const VON_KRIES_INVERSE: [[f64; 3]; 3] = [
+    [
+        1.859_936_387_455_839_7,
+        -1.129_381_618_580_091_6,
+        0.219_897_409_596_193_28,
+    ],
+    [
+        0.361_191_436_241_767_5,
+        0.638_812_463_285_042_2,
+        -0.000_006_370_596_838_657_058,
+    ],
+    [0.0, 0.0, 1.089_063_623_096_861_3],
+];
+
+const CAT02: [[f64; 3]; 3] = [
+    [0.7328, 0.4296, -0.1624],
+    [-0.7036, 1.6975, 0.0061],
+    [0.0030, 0.0136, 0.9834],
+];
+
+const CAT02_INVERSE: [[f64; 3]; 3] = [
+    [
+        1.096_123_820_835_514_2,
+        -0.278_869_000_218_287_26,
+        0.182_745_179_382_773_1,
+    ],
+    [
+        0.454_369_041_975_359_16,
+        0.473_533_154_307_411_7,
+        0.072_097_803_717_229_13,
+    ],
+    [
+        -0.009_627_608_738_429_355,
+        -0.005_698_031_216_113_421,
+        1.015_325_639_954_542_7,
+    ],
+];
+
+const IDENTITY: [[f64; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+pub(crate) fn matrix_product(lhs: &[[f64; 3]; 3], rhs: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut result = [[0.0; 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = (0..3).map(|k| lhs[i][k] * rhs[k][j]).sum();
+        }
+    }
+    result
+}
+
+/// Methods (von Kries style transforms) for predicting the appearance of a colour
+/// under a different illuminant.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChromaticAdaptation {
+    Bradford,
+    VonKries,
+    CAT02,
+    /// Naive scaling of the XYZ coordinates (usually the least accurate)
+    XYZScaling,
+}
+
+impl Default for ChromaticAdaptation {
+    fn default() -> Self {
+        ChromaticAdaptation::Bradford
+    }
+}
+
+impl ChromaticAdaptation {
+    fn cone_response_matrices(&self) -> (&'static [[f64; 3]; 3], &'static [[f64; 3]; 3]) {
+        match self {
+            ChromaticAdaptation::Bradford => (&BRADFORD, &BRADFORD_INVERSE),
+            ChromaticAdaptation::VonKries => (&VON_KRIES, &VON_KRIES_INVERSE),
+            ChromaticAdaptation::CAT02 => (&CAT02, &CAT02_INVERSE),
+            ChromaticAdaptation::XYZScaling => (&IDENTITY, &IDENTITY),
+        }
+    }
+
+    /// The matrix that maps XYZ coordinates relative to the `source` white point to
+    /// XYZ coordinates relative to the `destination` white point.
+    pub fn matrix(&self, source: Illuminant, destination: Illuminant) -> [[f64; 3]; 3] {
+        let (cone_response, cone_response_inverse) = self.cone_response_matrices();
+        let source_cone = apply_matrix(cone_response, source.white_point_array());
+        let destination_cone = apply_matrix(cone_response, destination.white_point_array());
+        let mut scaling = [[0.0; 3]; 3];
+        for i in 0..3 {
+            scaling[i][i] = destination_cone[i] / source_cone[i];
+        }
+        matrix_product(
+            cone_response_inverse,
+            &matrix_product(&scaling, cone_response),
+        )
+    }
+
+    /// The XYZ coordinates (relative to `destination`) of the colour with the given XYZ
+    /// coordinates relative to `source`.
+    pub fn adapt<F: ColourComponent>(
+        &self,
+        xyz: &XYZ<F>,
+        source: Illuminant,
+        destination: Illuminant,
+    ) -> XYZ<F> {
+        if source == destination {
+            *xyz
+        } else {
+            apply_matrix(&self.matrix(source, destination), [xyz.x, xyz.y, xyz.z]).into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod illuminant_tests {
+    use super::*;
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn white_points() {
+        assert_eq!(Illuminant::default().white_point::<f64>(), XYZ::from(D65));
+        assert_approx_eq!(
+            Illuminant::D50.white_point::<f64>(),
+            XYZ::from([0.964_22, 1.0, 0.825_21]),
+            0.000_1
+        );
+        assert_approx_eq!(
+            Illuminant::A.white_point::<f64>(),
+            XYZ::from([1.098_50, 1.0, 0.355_85]),
+            0.000_1
+        );
+        assert_approx_eq!(
+            Illuminant::E.white_point::<f64>(),
+            XYZ::from([1.0, 1.0, 1.0]),
+            0.000_000_001
+        );
+    }
+
+    #[test]
+    fn bradford_d65_to_d50() {
+        // Published values (Lindbloom) allowing for slightly different white points
+        let expected = [
+            [1.047_811_2, 0.022_886_6, -0.050_127_0],
+            [0.029_542_4, 0.990_484_4, -0.017_049_1],
+            [-0.009_234_5, 0.015_043_6, 0.752_131_6],
+        ];
+        let matrix = ChromaticAdaptation::Bradford.matrix(Illuminant::D65, Illuminant::D50);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert_approx_eq!(matrix[i][j], expected[i][j], 0.000_5);
+            }
+        }
+    }
+
+    #[test]
+    fn white_maps_to_white() {
+        for method in [
+            ChromaticAdaptation::Bradford,
+            ChromaticAdaptation::VonKries,
+            ChromaticAdaptation::CAT02,
+            ChromaticAdaptation::XYZScaling,
+        ]
+        .iter()
+        {
+            for source in Illuminant::ALL.iter() {
+                for destination in Illuminant::ALL.iter() {
+                    let white = method.adapt(&source.white_point::<f64>(), *source, *destination);
+                    assert_approx_eq!(white, destination.white_point(), 0.000_000_001);
+                    let xyz = XYZ::<f64>::from([0.3, 0.2, 0.4]);
+                    let round_trip = method.adapt(
+                        &method.adapt(&xyz, *source, *destination),
+                        *destination,
+                        *source,
+                    );
+                    assert_approx_eq!(round_trip, xyz, 0.000_000_001);
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright 2019 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
 //use std::slice::Iter;
 
+use crate::ColourMathError;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct XY {
     pub x: usize,
@@ -176,8 +178,43 @@ impl<P: Copy> From<(Size, &[P])> for GenericImage<P> {
     }
 }
 
+fn check_dimensions(length: usize, width: usize) -> Result<(), ColourMathError> {
+    if length.checked_rem(width) != Some(0) {
+        Err(ColourMathError::BadLength(format!(
+            "{} pixels for width {} (must be a non zero multiple of the width)",
+            length, width
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+impl<P: Copy> GenericImage<P> {
+    /// As `from((pixels, width))` but with an error if `pixels` can't be split into
+    /// whole rows of `width` pixels.
+    pub fn try_from_pixels(pixels: Vec<P>, width: usize) -> Result<Self, ColourMathError> {
+        check_dimensions(pixels.len(), width)?;
+        Ok((pixels, width).into())
+    }
+}
+
+use crate::histogram::{self, Distribution, Histogram};
 use crate::rgb::*;
-use crate::{ColourComponent, ColourInterface};
+use crate::{ColourComponent, ColourInterface, LinearRGB, RGBConstants, ScalarAttribute};
+
+impl<F: ColourComponent> GenericImage<RGB<F>> {
+    pub fn attribute_histogram(&self, attr: ScalarAttribute, bins: usize) -> Histogram<F> {
+        histogram::attribute_histogram(&self.pixels, attr, bins)
+    }
+
+    pub fn hue_angle_histogram(&self, bins: usize) -> Histogram<F> {
+        histogram::hue_angle_histogram(&self.pixels, bins)
+    }
+
+    pub fn attribute_distribution(&self, attr: ScalarAttribute) -> Option<Distribution<F>> {
+        histogram::attribute_distribution(&self.pixels, attr)
+    }
+}
 
 pub struct OpaqueImage<F: ColourComponent> {
     pixels: Vec<RGB<F>>,
@@ -211,6 +248,22 @@ impl<F: ColourComponent> OpaqueImage<F> {
         sum / F::from_usize(self.pixels.len()).unwrap()
     }
 
+    pub fn average_linear_value(&self) -> F {
+        let sum: F = self.pixels.iter().map(|p| p.linear_value()).sum();
+        sum / F::from_usize(self.pixels.len()).unwrap()
+    }
+
+    /// Average colour with the pixels blended in linear light
+    pub fn average_rgb(&self) -> RGB<F> {
+        let weight = F::from_usize(self.pixels.len()).unwrap().recip();
+        let average = self
+            .pixels
+            .iter()
+            .map(|p| p.linear_rgb() * weight)
+            .fold(LinearRGB::BLACK, |acc, p| acc + p);
+        average.into()
+    }
+
     pub fn average_chroma(&self) -> F {
         let sum: F = self.pixels.iter().map(|p| p.chroma()).sum();
         sum / F::from_usize(self.pixels.len()).unwrap()
@@ -220,6 +273,18 @@ impl<F: ColourComponent> OpaqueImage<F> {
         let sum: F = self.pixels.iter().map(|p| p.warmth()).sum();
         sum / F::from_usize(self.pixels.len()).unwrap()
     }
+
+    pub fn attribute_histogram(&self, attr: ScalarAttribute, bins: usize) -> Histogram<F> {
+        histogram::attribute_histogram(&self.pixels, attr, bins)
+    }
+
+    pub fn hue_angle_histogram(&self, bins: usize) -> Histogram<F> {
+        histogram::hue_angle_histogram(&self.pixels, bins)
+    }
+
+    pub fn attribute_distribution(&self, attr: ScalarAttribute) -> Option<Distribution<F>> {
+        histogram::attribute_distribution(&self.pixels, attr)
+    }
 }
 
 impl<F: ColourComponent> std::ops::Index<usize> for OpaqueImage<F> {
@@ -240,6 +305,18 @@ impl<F: ColourComponent> std::ops::IndexMut<usize> for OpaqueImage<F> {
     }
 }
 
+impl<F: ColourComponent> OpaqueImage<F> {
+    /// As `from((pixels, width))` but with an error if `pixels` can't be split into
+    /// whole rows of `width` pixels or any of them is not a valid colour.
+    pub fn try_from_pixels(pixels: Vec<RGB<F>>, width: usize) -> Result<Self, ColourMathError> {
+        check_dimensions(pixels.len(), width)?;
+        if let Some(rgb) = pixels.iter().find(|rgb| !rgb.is_valid()) {
+            return Err(ColourMathError::OutOfRange(format!("pixel {:?}", rgb)));
+        }
+        Ok(Self { pixels, width })
+    }
+}
+
 impl<F: ColourComponent> From<(Vec<RGB<F>>, usize)> for OpaqueImage<F> {
     fn from(data: (Vec<RGB<F>>, usize)) -> Self {
         debug_assert_eq!(data.0.len() % data.1, 0);
@@ -285,6 +362,7 @@ impl<F: ColourComponent> From<(&[u8], usize, usize)> for OpaqueImage<F> {
 mod image_tests {
     use super::*;
     use crate::{rgb::RGB, ColourInterface};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
     use std::marker::PhantomData;
 
     #[derive(Default)]
@@ -366,4 +444,39 @@ mod image_tests {
             }
         }
     }
+
+    #[test]
+    fn linear_light_averages() {
+        let image = OpaqueImage::<f64>::from((vec![RGB::BLACK, RGB::WHITE], 2));
+        assert_approx_eq!(image.average_value(), 0.5);
+        assert_approx_eq!(image.average_linear_value(), 0.5);
+        let image = OpaqueImage::<f64>::from((vec![RGB::RED, RGB::GREEN, RGB::BLUE], 3));
+        assert_approx_eq!(image.average_linear_value(), 1.0 / 3.0);
+        let average = image.average_rgb();
+        assert!(average.is_grey());
+        assert!(average.value() > image.average_value());
+    }
+
+    #[test]
+    fn fallible_construction() {
+        let pixels = vec![RGB::<f64>::RED; 6];
+        let image = GenericImage::try_from_pixels(pixels.clone(), 3).unwrap();
+        assert_eq!(image.height(), 2);
+        for width in [0, 4].iter() {
+            assert!(matches!(
+                GenericImage::try_from_pixels(pixels.clone(), *width),
+                Err(ColourMathError::BadLength(_))
+            ));
+        }
+        let image = OpaqueImage::try_from_pixels(pixels.clone(), 2).unwrap();
+        assert_eq!(image.height(), 3);
+        assert!(matches!(
+            OpaqueImage::try_from_pixels(pixels, 4),
+            Err(ColourMathError::BadLength(_))
+        ));
+        assert!(matches!(
+            OpaqueImage::try_from_pixels(vec![RGB::<f64>::RED, RGB([0.0, 2.0, 0.0])], 2),
+            Err(ColourMathError::OutOfRange(_))
+        ));
+    }
 }
@@ -10,20 +10,129 @@ use num_traits_plus::float_plus::*;
 
 pub use normalised_angles::{Degrees, DegreesConst, RadiansConst};
 
+// For colour types whose attributes are all derived from their RGB equivalent.
+// Any extra (overriding) trait methods can be appended to the invocation.
+macro_rules! impl_colour_interface_via_rgb {
+    ( $type:ident ) => {
+        impl_colour_interface_via_rgb!($type, {});
+    };
+    ( $type:ident, { $( $extra:tt )* } ) => {
+        impl<F: $crate::ColourComponent> $crate::ColourInterface<F> for $type<F> {
+            fn rgb(&self) -> $crate::RGB<F> {
+                self.into()
+            }
+
+            fn rgba(&self) -> $crate::RGBA<F> {
+                self.rgb().rgba()
+            }
+
+            fn hcv(&self) -> $crate::HCV<F> {
+                self.rgb().hcv()
+            }
+
+            fn hue(&self) -> Option<$crate::Hue<F>> {
+                self.rgb().hue()
+            }
+
+            fn hue_angle(&self) -> Option<$crate::Degrees<F>> {
+                self.rgb().hue_angle()
+            }
+
+            fn is_grey(&self) -> bool {
+                self.rgb().is_grey()
+            }
+
+            fn chroma(&self) -> F {
+                self.rgb().chroma()
+            }
+
+            fn greyness(&self) -> F {
+                self.rgb().greyness()
+            }
+
+            fn value(&self) -> F {
+                self.rgb().value()
+            }
+
+            fn warmth(&self) -> F {
+                self.rgb().warmth()
+            }
+
+            fn best_foreground_rgb(&self) -> $crate::RGB<F> {
+                self.rgb().best_foreground_rgb()
+            }
+
+            fn monochrome_rgb(&self) -> $crate::RGB<F> {
+                self.rgb().monochrome_rgb()
+            }
+
+            fn max_chroma_rgb(&self) -> $crate::RGB<F> {
+                self.rgb().max_chroma_rgb()
+            }
+
+            fn warmth_rgb(&self) -> $crate::RGB<F> {
+                self.rgb().warmth_rgb()
+            }
+
+            $( $extra )*
+        }
+    };
+}
+
 pub mod attributes;
 pub mod chroma;
+pub mod cie;
+pub mod cmyk;
+pub mod contrast;
+pub mod css;
+pub mod cvd;
+pub mod difference;
+pub mod error;
+pub mod gradient;
+pub mod harmony;
 pub mod hcv;
+pub mod histogram;
+pub mod hsl;
+pub mod hsv;
 pub mod hue;
+pub mod illuminant;
 pub mod image;
+pub mod linear_rgb;
 pub mod manipulator;
+pub mod named_colours;
+pub mod oklab;
+pub mod palette;
+pub mod pigment;
+pub mod quantise;
+pub mod recipe;
 pub mod rgb;
+pub mod rgb_space;
 pub mod rgba;
+pub mod serde_readable;
+pub mod temperature;
 pub mod urgb;
 pub mod urgba;
 
-pub use crate::hcv::HCV;
+pub use crate::cie::{LCh, Lab, XYZ};
+pub use crate::cmyk::{CMYKConverter, CMYK};
+pub use crate::cvd::{CVDSimulator, ColourVisionDeficiency};
+pub use crate::difference::DifferenceMetric;
+pub use crate::error::ColourMathError;
+pub use crate::gradient::{Easing, Gradient, HuePath, Interpolation};
+pub use crate::harmony::Harmony;
+pub use crate::hcv::{HCVError, HCVGamutMapping, HCV};
+pub use crate::hsl::HSL;
+pub use crate::hsv::HSV;
 pub use crate::hue::Hue;
+pub use crate::illuminant::{ChromaticAdaptation, Illuminant};
+pub use crate::linear_rgb::LinearRGB;
+pub use crate::oklab::{Oklab, Oklch};
+pub use crate::palette::{Palette, PaletteEntry, Swatch};
+pub use crate::pigment::Pigment;
+pub use crate::quantise::Quantiser;
+pub use crate::recipe::{Recipe, RecipeSolver};
 pub use crate::rgb::{IndicesValueOrder, RGB};
+pub use crate::rgb_space::{GamutMapping, RGBSpace, TaggedRGB};
 pub use crate::rgba::RGBA;
 pub use crate::urgb::{RGB16, RGB8, URGB};
 pub use crate::urgba::{RGBA16, RGBA8, URGBA};
@@ -82,6 +191,16 @@ impl ColourComponent for f64 {
     const COS_120: Self = -0.5;
 }
 
+/// `value` (e.g. a literal constant) as a `ColourComponent`.
+pub(crate) fn f<F: ColourComponent>(value: f64) -> F {
+    F::from(value).unwrap()
+}
+
+/// `value` limited to the range 0.0 to 1.0.
+pub(crate) fn clamped<F: ColourComponent>(value: F) -> F {
+    value.max(F::ZERO).min(F::ONE)
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CCI {
     Red,
@@ -167,6 +286,88 @@ pub trait ColourInterface<F: ColourComponent> {
 
     fn warmth_rgb(&self) -> RGB<F>;
 
+    fn linear_rgb(&self) -> LinearRGB<F> {
+        self.rgb().into()
+    }
+
+    /// Value calculated in linear light rather than from gamma-encoded components
+    fn linear_value(&self) -> F {
+        self.linear_rgb().value()
+    }
+
+    fn linear_monochrome_rgb(&self) -> RGB<F> {
+        let value = self.linear_value();
+        LinearRGB::from([value, value, value]).into()
+    }
+
+    fn xyz(&self) -> XYZ<F> {
+        self.rgb().into()
+    }
+
+    fn lab(&self) -> Lab<F> {
+        self.rgb().into()
+    }
+
+    fn lch(&self) -> LCh<F> {
+        self.rgb().into()
+    }
+
+    fn oklab(&self) -> Oklab<F> {
+        self.rgb().into()
+    }
+
+    fn oklch(&self) -> Oklch<F> {
+        self.rgb().into()
+    }
+
+    fn hsv(&self) -> HSV<F> {
+        self.rgb().into()
+    }
+
+    fn hsl(&self) -> HSL<F> {
+        self.rgb().into()
+    }
+
+    /// A physical counterpart to `warmth()`.  See `temperature::correlated_colour_temperature()`
+    /// for the circumstances in which this is `None`.
+    fn correlated_colour_temperature(&self) -> Option<F> {
+        temperature::correlated_colour_temperature(&self.xyz())
+    }
+
+    /// Relative luminance as defined by WCAG 2.x
+    fn relative_luminance(&self) -> F {
+        contrast::relative_luminance(&self.rgb())
+    }
+
+    fn wcag_contrast_ratio(&self, other: &impl ColourInterface<F>) -> F {
+        contrast::wcag_contrast_ratio(&self.rgb(), &other.rgb())
+    }
+
+    /// APCA lightness contrast of `text` when this colour is the background
+    fn apca_contrast_for_text(&self, text: &impl ColourInterface<F>) -> F {
+        contrast::apca_contrast(&text.rgb(), &self.rgb())
+    }
+
+    /// The candidate with the highest WCAG contrast ratio against this colour (or
+    /// `best_foreground_rgb()` if `candidates` is empty)
+    fn best_foreground_rgb_from(&self, candidates: &[RGB<F>]) -> RGB<F> {
+        match contrast::best_foreground_rgb_from(&self.rgb(), candidates) {
+            Some(rgb) => rgb,
+            None => self.best_foreground_rgb(),
+        }
+    }
+
+    /// How this colour appears to somebody with the given colour vision deficiency where
+    /// `severity` is in the range 0.0 (normal vision) to 1.0 (dichromacy).
+    fn cvd_simulated_rgb(&self, deficiency: ColourVisionDeficiency, severity: F) -> RGB<F> {
+        CVDSimulator::new(deficiency, severity).simulate(&self.rgb())
+    }
+
+    /// How different `other` is from this colour as measured by `metric`
+    fn difference(&self, other: &impl ColourInterface<F>, metric: DifferenceMetric) -> F {
+        difference::difference(self, other, metric)
+    }
+
     fn scalar_attribute(&self, attr: ScalarAttribute) -> F {
         match attr {
             ScalarAttribute::Chroma => self.chroma(),
@@ -0,0 +1,276 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use std::ops::{Add, Index, Mul};
+
+use num_traits_plus::float_plus::*;
+
+use crate::{urgb::UnsignedComponent, ColourComponent, HueConstants, RGBConstants, CCI, RGB, URGB};
+
+/// Decode a gamma-encoded sRGB component into linear light.
+pub fn srgb_to_linear<F: ColourComponent>(encoded: F) -> F {
+    debug_assert!(encoded.is_proportion(), "encoded: {:?}", encoded);
+    if encoded <= F::from(0.04045).unwrap() {
+        encoded / F::from(12.92).unwrap()
+    } else if encoded < F::ONE {
+        ((encoded + F::from(0.055).unwrap()) / F::from(1.055).unwrap())
+            .powf(F::from(2.4).unwrap())
+            .min(F::ONE)
+    } else {
+        F::ONE
+    }
+}
+
+/// Encode a linear light component as a gamma-encoded sRGB component.
+pub fn linear_to_srgb<F: ColourComponent>(linear: F) -> F {
+    debug_assert!(linear.is_proportion(), "linear: {:?}", linear);
+    if linear <= F::from(0.003_130_8).unwrap() {
+        linear * F::from(12.92).unwrap()
+    } else if linear < F::ONE {
+        (F::from(1.055).unwrap() * linear.powf(F::from(2.4).unwrap().recip())
+            - F::from(0.055).unwrap())
+        .min(F::ONE)
+    } else {
+        F::ONE
+    }
+}
+
+/// An RGB colour whose components are proportional to light intensity
+/// (i.e. they have had the sRGB transfer function removed).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Hash, PartialEq, Default)]
+pub struct LinearRGB<F: ColourComponent>(pub(crate) [F; 3]);
+
+impl<F: ColourComponent> HueConstants for LinearRGB<F> {
+    const RED: Self = Self([F::ONE, F::ZERO, F::ZERO]);
+    const GREEN: Self = Self([F::ZERO, F::ONE, F::ZERO]);
+    const BLUE: Self = Self([F::ZERO, F::ZERO, F::ONE]);
+
+    const CYAN: Self = Self([F::ZERO, F::ONE, F::ONE]);
+    const MAGENTA: Self = Self([F::ONE, F::ZERO, F::ONE]);
+    const YELLOW: Self = Self([F::ONE, F::ONE, F::ZERO]);
+}
+
+impl<F: ColourComponent> RGBConstants for LinearRGB<F> {
+    const WHITE: Self = Self([F::ONE, F::ONE, F::ONE]);
+    const BLACK: Self = Self([F::ZERO, F::ZERO, F::ZERO]);
+}
+
+impl<F: ColourComponent> LinearRGB<F> {
+    pub fn iter(&self) -> impl Iterator<Item = &F> {
+        self.0.iter()
+    }
+
+    /// The mean of the linear light components.
+    pub fn value(&self) -> F {
+        (self.0.iter().copied().sum::<F>() / F::THREE).min(F::ONE)
+    }
+
+    /// Blend with `other` in linear light where `weight` is the proportion of `other`.
+    pub fn mixed_with(&self, other: &Self, weight: F) -> Self {
+        debug_assert!(weight.is_proportion(), "weight: {:?}", weight);
+        *self * (F::ONE - weight) + *other * weight
+    }
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for LinearRGB<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        for i in 0..3 {
+            if !self.0[i].approx_eq(&other.0[i], max_diff) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<F: ColourComponent> Index<CCI> for LinearRGB<F> {
+    type Output = F;
+
+    fn index(&self, index: CCI) -> &F {
+        match index {
+            CCI::Red => &self.0[0],
+            CCI::Green => &self.0[1],
+            CCI::Blue => &self.0[2],
+        }
+    }
+}
+
+impl<F: ColourComponent> Add for LinearRGB<F> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let array: [F; 3] = [
+            self.0[0] + other.0[0],
+            self.0[1] + other.0[1],
+            self.0[2] + other.0[2],
+        ];
+        array.into()
+    }
+}
+
+impl<F: ColourComponent> Mul<F> for LinearRGB<F> {
+    type Output = Self;
+
+    fn mul(self, scalar: F) -> Self {
+        let array: [F; 3] = [self.0[0] * scalar, self.0[1] * scalar, self.0[2] * scalar];
+        array.into()
+    }
+}
+
+impl<F: ColourComponent> From<[F; 3]> for LinearRGB<F> {
+    fn from(array: [F; 3]) -> Self {
+        debug_assert!(array.iter().all(|x| (*x).is_proportion()), "{:?}", array);
+        Self(array)
+    }
+}
+
+impl<F: ColourComponent> From<&LinearRGB<F>> for [F; 3] {
+    fn from(linear_rgb: &LinearRGB<F>) -> [F; 3] {
+        linear_rgb.0
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for LinearRGB<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        Self([
+            srgb_to_linear(rgb.0[0]),
+            srgb_to_linear(rgb.0[1]),
+            srgb_to_linear(rgb.0[2]),
+        ])
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for LinearRGB<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&LinearRGB<F>> for RGB<F> {
+    fn from(linear_rgb: &LinearRGB<F>) -> Self {
+        Self([
+            linear_to_srgb(linear_rgb.0[0]),
+            linear_to_srgb(linear_rgb.0[1]),
+            linear_to_srgb(linear_rgb.0[2]),
+        ])
+    }
+}
+
+impl<F: ColourComponent> From<LinearRGB<F>> for RGB<F> {
+    fn from(linear_rgb: LinearRGB<F>) -> Self {
+        (&linear_rgb).into()
+    }
+}
+
+impl<F, U> From<&URGB<U>> for LinearRGB<F>
+where
+    F: ColourComponent,
+    U: UnsignedComponent,
+{
+    fn from(urgb: &URGB<U>) -> Self {
+        RGB::<F>::from(urgb).into()
+    }
+}
+
+impl<F, U> From<URGB<U>> for LinearRGB<F>
+where
+    F: ColourComponent,
+    U: UnsignedComponent,
+{
+    fn from(urgb: URGB<U>) -> Self {
+        (&urgb).into()
+    }
+}
+
+impl<U, F> From<&LinearRGB<F>> for URGB<U>
+where
+    F: ColourComponent,
+    U: UnsignedComponent,
+{
+    fn from(linear_rgb: &LinearRGB<F>) -> Self {
+        RGB::<F>::from(linear_rgb).into()
+    }
+}
+
+impl<U, F> From<LinearRGB<F>> for URGB<U>
+where
+    F: ColourComponent,
+    U: UnsignedComponent,
+{
+    fn from(linear_rgb: LinearRGB<F>) -> Self {
+        (&linear_rgb).into()
+    }
+}
+
+#[cfg(test)]
+mod linear_rgb_tests {
+    use super::*;
+    use crate::ColourInterface;
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn transfer_functions() {
+        assert_eq!(srgb_to_linear(0.0_f64), 0.0);
+        assert_eq!(srgb_to_linear(1.0_f64), 1.0);
+        assert_eq!(linear_to_srgb(0.0_f64), 0.0);
+        assert_eq!(linear_to_srgb(1.0_f64), 1.0);
+        assert_approx_eq!(
+            srgb_to_linear(0.5_f64),
+            0.214_041_140_482_232_5,
+            0.000_000_001
+        );
+        for i in 0..=100 {
+            let encoded = i as f64 / 100.0;
+            assert_approx_eq!(
+                linear_to_srgb(srgb_to_linear(encoded)),
+                encoded,
+                0.000_000_001
+            );
+        }
+    }
+
+    #[test]
+    fn constants_map_to_constants() {
+        for (rgb, linear_rgb) in RGB::<f64>::PRIMARIES
+            .iter()
+            .zip(LinearRGB::<f64>::PRIMARIES.iter())
+        {
+            assert_eq!(LinearRGB::from(rgb), *linear_rgb);
+            assert_eq!(RGB::from(linear_rgb), *rgb);
+        }
+        for (rgb, linear_rgb) in RGB::<f64>::SECONDARIES
+            .iter()
+            .zip(LinearRGB::<f64>::SECONDARIES.iter())
+        {
+            assert_eq!(LinearRGB::from(rgb), *linear_rgb);
+            assert_eq!(RGB::from(linear_rgb), *rgb);
+        }
+        assert_eq!(RGB::<f32>::WHITE.linear_rgb(), LinearRGB::WHITE);
+        assert_eq!(RGB::<f32>::BLACK.linear_rgb(), LinearRGB::BLACK);
+    }
+
+    #[test]
+    fn urgb_round_trips() {
+        for i in 0..=255_u8 {
+            let urgb = URGB::<u8>::from([i, 255 - i, i / 2]);
+            let linear_rgb = LinearRGB::<f64>::from(urgb);
+            assert_eq!(URGB::<u8>::from(linear_rgb), urgb);
+            let linear_rgb = LinearRGB::<f32>::from(urgb);
+            assert_eq!(URGB::<u8>::from(linear_rgb), urgb);
+        }
+        for i in (0..=0xFFFF_u16).step_by(257) {
+            let urgb = URGB::<u16>::from([i, 0xFFFF - i, i / 3]);
+            let linear_rgb = LinearRGB::<f64>::from(urgb);
+            assert_eq!(URGB::<u16>::from(linear_rgb), urgb);
+        }
+    }
+
+    #[test]
+    fn mixing_in_linear_light() {
+        let mixed = LinearRGB::<f64>::BLACK.mixed_with(&LinearRGB::WHITE, 0.5);
+        assert_approx_eq!(mixed, LinearRGB::from([0.5, 0.5, 0.5]));
+        let rgb = RGB::<f64>::from(mixed);
+        assert_approx_eq!(rgb, RGB::from([0.735_356_983_052_449_3; 3]), 0.000_000_001);
+        assert_approx_eq!(rgb.linear_value(), 0.5);
+        assert_approx_eq!(RGB::<f64>::YELLOW.linear_value(), 2.0 / 3.0);
+    }
+}
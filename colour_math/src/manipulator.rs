@@ -1,6 +1,13 @@
 // Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
 
-use crate::{chroma::*, hcv::*, rgb::*, ColourComponent, Degrees, HueIfce};
+use crate::{
+    chroma::*,
+    error::{self, ColourMathError},
+    hcv::*,
+    oklab::Oklch,
+    rgb::*,
+    ColourComponent, Degrees, HueIfce,
+};
 
 #[derive(Clone, Copy)]
 pub enum RotationPolicy {
@@ -14,26 +21,67 @@ impl Default for RotationPolicy {
     }
 }
 
+/// The colour space in which the manipulator makes its adjustments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ManipulationMode {
+    /// Chroma, value and hue steps are geometric in RGB space
+    HCV,
+    /// Chroma, lightness and hue steps are perceptually even (via Oklch)
+    Oklch,
+}
+
+impl Default for ManipulationMode {
+    fn default() -> Self {
+        ManipulationMode::HCV
+    }
+}
+
 #[derive(Default)]
 pub struct ColourManipulator<F: ColourComponent + ChromaTolerance> {
     hcv: HCV<F>,
     clamped: bool,
     rotation_policy: RotationPolicy,
     saved_hue_data: HueData<F>,
+    mode: ManipulationMode,
+    oklch: Oklch<F>,
 }
 
 impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
     pub fn rgb(&self) -> RGB<F> {
-        (&self.hcv).into()
+        match self.mode {
+            ManipulationMode::HCV => self.hcv.rgb_with_mapping(HCVGamutMapping::default()),
+            ManipulationMode::Oklch => (&self.oklch).into(),
+        }
     }
 
+    pub fn hcv(&self) -> HCV<F> {
+        match self.mode {
+            ManipulationMode::HCV => self.hcv,
+            ManipulationMode::Oklch => self.rgb().into(),
+        }
+    }
+
+    pub fn mode(&self) -> ManipulationMode {
+        self.mode
+    }
+
+    /// HCVs that don't represent a valid colour are brought into gamut by reducing
+    /// their chroma.
     pub fn set_hcv(&mut self, hcv: &HCV<F>) {
-        self.hcv = *hcv;
+        self.hcv = hcv.gamut_mapped(HCVGamutMapping::default());
         if let Some(hue_data) = self.hcv.hue_data() {
             self.saved_hue_data = hue_data;
         } else {
             self.saved_hue_data = HueData::default();
         }
+        if self.mode == ManipulationMode::Oklch {
+            let hue = self.oklch.h;
+            self.oklch = self.hcv.rgb_with_mapping(HCVGamutMapping::default()).into();
+            if self.oklch.c == F::ZERO {
+                // keep the previous hue in case chroma is added back
+                self.oklch.h = hue;
+            }
+        }
     }
 
     pub fn set_rgb(&mut self, rgb: &RGB<F>) {
@@ -42,6 +90,9 @@ impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
 
     pub fn decr_chroma(&mut self, delta: F) -> bool {
         debug_assert!(delta.is_proportion());
+        if self.mode == ManipulationMode::Oklch {
+            return self.oklch_decr_chroma(delta);
+        }
         if self.hcv.chroma == F::ZERO {
             false
         } else {
@@ -68,6 +119,9 @@ impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
 
     pub fn incr_chroma(&mut self, delta: F) -> bool {
         debug_assert!(delta.is_proportion());
+        if self.mode == ManipulationMode::Oklch {
+            return self.oklch_incr_chroma(delta);
+        }
         if self.hcv.chroma == F::ONE {
             false
         } else {
@@ -142,6 +196,9 @@ impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
 
     pub fn decr_value(&mut self, delta: F) -> bool {
         debug_assert!(delta.is_proportion());
+        if self.mode == ManipulationMode::Oklch {
+            return self.oklch_decr_value(delta);
+        }
         if self.hcv.sum == F::ZERO {
             false
         } else {
@@ -152,6 +209,9 @@ impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
 
     pub fn incr_value(&mut self, delta: F) -> bool {
         debug_assert!(delta.is_proportion());
+        if self.mode == ManipulationMode::Oklch {
+            return self.oklch_incr_value(delta);
+        }
         if self.hcv.sum == F::THREE {
             false
         } else {
@@ -160,7 +220,38 @@ impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
         }
     }
 
+    /// As `decr_chroma()` but with an error (and no change) unless `delta` is in
+    /// the range 0.0 to 1.0.
+    pub fn try_decr_chroma(&mut self, delta: F) -> Result<bool, ColourMathError> {
+        error::check_proportions("chroma delta", &[delta])?;
+        Ok(self.decr_chroma(delta))
+    }
+
+    /// As `incr_chroma()` but with an error (and no change) unless `delta` is in
+    /// the range 0.0 to 1.0.
+    pub fn try_incr_chroma(&mut self, delta: F) -> Result<bool, ColourMathError> {
+        error::check_proportions("chroma delta", &[delta])?;
+        Ok(self.incr_chroma(delta))
+    }
+
+    /// As `decr_value()` but with an error (and no change) unless `delta` is in
+    /// the range 0.0 to 1.0.
+    pub fn try_decr_value(&mut self, delta: F) -> Result<bool, ColourMathError> {
+        error::check_proportions("value delta", &[delta])?;
+        Ok(self.decr_value(delta))
+    }
+
+    /// As `incr_value()` but with an error (and no change) unless `delta` is in
+    /// the range 0.0 to 1.0.
+    pub fn try_incr_value(&mut self, delta: F) -> Result<bool, ColourMathError> {
+        error::check_proportions("value delta", &[delta])?;
+        Ok(self.incr_value(delta))
+    }
+
     pub fn rotate(&mut self, angle: Degrees<F>) -> bool {
+        if self.mode == ManipulationMode::Oklch {
+            return self.oklch_rotate(angle);
+        }
         if let Some(hue_data) = self.hcv.hue_data {
             let hue_angle = hue_data.hue_angle();
             let new_angle = hue_angle + angle;
@@ -194,6 +285,143 @@ impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
     }
 }
 
+// Oklch mode: lightness, chroma and hue are adjusted in Oklch and the
+// result is kept within the sRGB gamut.
+impl<F: ColourComponent + ChromaTolerance> ColourManipulator<F> {
+    fn sync_hcv(&mut self) {
+        self.hcv = RGB::from(&self.oklch).into();
+        if let Some(hue_data) = self.hcv.hue_data() {
+            self.saved_hue_data = hue_data;
+        }
+    }
+
+    // The lightness nearest to the colour's current lightness at which its
+    // chroma can be displayed or, failing that, the lightness where the hue
+    // achieves its greatest chroma (along with that chroma).
+    fn lightness_for_chroma(&self, oklch: &Oklch<F>) -> (F, F) {
+        let step = F::from(1.0 / 256.0).unwrap();
+        let mut best = (oklch.l, oklch.max_chroma_in_gamut());
+        let mut probe = *oklch;
+        let mut offset = step;
+        while offset < F::ONE {
+            for l in [oklch.l - offset, oklch.l + offset].iter() {
+                if *l > F::ZERO && *l < F::ONE {
+                    probe.l = *l;
+                    let max_chroma = probe.max_chroma_in_gamut();
+                    if max_chroma >= oklch.c {
+                        return (*l, oklch.c);
+                    } else if max_chroma > best.1 {
+                        best = (*l, max_chroma);
+                    }
+                }
+            }
+            offset += step;
+        }
+        best
+    }
+
+    fn oklch_decr_chroma(&mut self, delta: F) -> bool {
+        if self.oklch.c == F::ZERO {
+            false
+        } else {
+            self.oklch.c = (self.oklch.c - delta).max(F::ZERO);
+            self.sync_hcv();
+            true
+        }
+    }
+
+    fn oklch_incr_chroma(&mut self, delta: F) -> bool {
+        let cur_chroma = self.oklch.c;
+        let max_chroma = self.oklch.max_chroma_in_gamut();
+        if self.clamped || cur_chroma + delta <= max_chroma {
+            self.oklch.c = (cur_chroma + delta).min(max_chroma).max(cur_chroma);
+        } else {
+            let mut target = self.oklch;
+            target.c = cur_chroma + delta;
+            let (l, c) = self.lightness_for_chroma(&target);
+            self.oklch.l = l;
+            self.oklch.c = c.max(cur_chroma);
+        }
+        if cur_chroma != self.oklch.c {
+            self.sync_hcv();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn set_lightness(&mut self, new_l: F) -> bool {
+        let cur_l = self.oklch.l;
+        let mut target = self.oklch;
+        target.l = new_l;
+        if target.c > target.max_chroma_in_gamut() {
+            if self.clamped {
+                // Find where the current chroma reaches the edge of the gamut
+                let (mut good, mut bad) = (cur_l, new_l);
+                for _ in 0..32 {
+                    target.l = (good + bad) * F::HALF;
+                    if target.c <= target.max_chroma_in_gamut() {
+                        good = target.l;
+                    } else {
+                        bad = target.l;
+                    }
+                }
+                target.l = good;
+            } else {
+                target.c = target.max_chroma_in_gamut();
+            }
+        }
+        self.oklch = target;
+        if cur_l != self.oklch.l {
+            self.sync_hcv();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn oklch_decr_value(&mut self, delta: F) -> bool {
+        if self.oklch.l <= F::ZERO {
+            false
+        } else {
+            self.set_lightness((self.oklch.l - delta).max(F::ZERO))
+        }
+    }
+
+    fn oklch_incr_value(&mut self, delta: F) -> bool {
+        if self.oklch.l >= F::ONE {
+            false
+        } else {
+            self.set_lightness((self.oklch.l + delta).min(F::ONE))
+        }
+    }
+
+    fn oklch_rotate(&mut self, angle: Degrees<F>) -> bool {
+        if self.oklch.c == F::ZERO || angle == Degrees::DEG_0 {
+            return false;
+        }
+        let mut target = self.oklch;
+        target.h = (self.oklch.hue_angle() + angle).degrees();
+        if target.h < F::ZERO {
+            target.h += F::DEG_360;
+        }
+        let max_chroma = target.max_chroma_in_gamut();
+        if target.c > max_chroma {
+            match self.rotation_policy {
+                RotationPolicy::FavourChroma => {
+                    let (l, c) = self.lightness_for_chroma(&target);
+                    target.l = l;
+                    target.c = c;
+                }
+                RotationPolicy::FavourValue => target.c = max_chroma,
+            }
+        }
+        self.oklch = target;
+        self.sync_hcv();
+        true
+    }
+}
+
 #[derive(Default)]
 pub struct ColourManipulatorBuilder<F>
 where
@@ -202,6 +430,7 @@ where
     init_hcv: Option<HCV<F>>,
     clamped: bool,
     rotation_policy: RotationPolicy,
+    mode: ManipulationMode,
 }
 
 impl<F> ColourManipulatorBuilder<F>
@@ -232,10 +461,16 @@ where
         self
     }
 
+    pub fn mode(&mut self, mode: ManipulationMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
     pub fn build(&self) -> ColourManipulator<F> {
         let mut manipulator = ColourManipulator::<F>::default();
         manipulator.clamped = self.clamped;
         manipulator.rotation_policy = self.rotation_policy;
+        manipulator.mode = self.mode;
         if let Some(init_hcv) = self.init_hcv {
             manipulator.set_hcv(&init_hcv);
         };
@@ -246,6 +481,7 @@ where
 #[cfg(test)]
 mod hcv_manipulator_tests {
     use super::*;
+    use crate::{ColourInterface, RGBConstants};
     use num_traits_plus::{assert_approx_eq, float_plus::*};
 
     #[test]
@@ -597,4 +833,76 @@ mod hcv_manipulator_tests {
             }
         }
     }
+
+    #[test]
+    fn oklch_value_steps_are_even() {
+        for clamped in &[true, false] {
+            let mut manipulator = ColourManipulatorBuilder::<f64>::new()
+                .mode(ManipulationMode::Oklch)
+                .clamped(*clamped)
+                .build();
+            assert_eq!(manipulator.mode(), ManipulationMode::Oklch);
+            assert!(!manipulator.decr_value(0.1));
+            let mut expected = 0.0;
+            while manipulator.incr_value(0.1) {
+                expected = (expected + 0.1_f64).min(1.0);
+                assert_approx_eq!(manipulator.rgb().oklch().l(), expected, 0.000_000_001);
+            }
+            assert_approx_eq!(manipulator.rgb(), RGB::WHITE, 0.000_000_001);
+        }
+    }
+
+    #[test]
+    fn oklch_rotation_keeps_lightness() {
+        let mut manipulator = ColourManipulatorBuilder::<f64>::new()
+            .mode(ManipulationMode::Oklch)
+            .rotation_policy(RotationPolicy::FavourValue)
+            .build();
+        manipulator.set_rgb(&[0.6, 0.5, 0.4].into());
+        assert!(!manipulator.rotate(Degrees::DEG_0));
+        let lightness = manipulator.rgb().oklch().l();
+        for _ in 0..12 {
+            assert!(manipulator.rotate(Degrees::DEG_30));
+            assert_approx_eq!(manipulator.rgb().oklch().l(), lightness, 0.000_001);
+        }
+        manipulator.set_rgb(&RGB::WHITE);
+        assert!(!manipulator.rotate(Degrees::DEG_30));
+    }
+
+    #[test]
+    fn oklch_chroma_stays_in_gamut() {
+        for clamped in &[true, false] {
+            let mut manipulator = ColourManipulatorBuilder::<f64>::new()
+                .mode(ManipulationMode::Oklch)
+                .clamped(*clamped)
+                .init_rgb(&[0.5, 0.4, 0.3].into())
+                .build();
+            while manipulator.incr_chroma(0.01) {
+                assert!(manipulator.rgb().oklch().is_in_gamut());
+            }
+            assert!(!manipulator.rgb().is_grey());
+            while manipulator.decr_chroma(0.01) {}
+            assert!(manipulator.rgb().is_grey());
+        }
+    }
+
+    #[test]
+    fn fallible_adjustments() {
+        let mut manipulator = ColourManipulatorBuilder::<f64>::new().clamped(true).build();
+        manipulator.set_rgb(&RGB::from([0.5, 0.5, 0.5]));
+        assert!(manipulator.try_incr_value(0.1).unwrap());
+        assert_approx_eq!(manipulator.rgb().value(), 0.6);
+        for delta in [1.5, -0.1, f64::NAN].iter() {
+            assert!(matches!(
+                manipulator.try_incr_value(*delta),
+                Err(ColourMathError::OutOfRange(_))
+            ));
+            assert!(manipulator.try_decr_value(*delta).is_err());
+            assert!(manipulator.try_incr_chroma(*delta).is_err());
+            assert!(manipulator.try_decr_chroma(*delta).is_err());
+        }
+        assert_approx_eq!(manipulator.rgb().value(), 0.6);
+        assert!(manipulator.try_decr_value(0.1).unwrap());
+        assert!(!manipulator.try_decr_chroma(0.1).unwrap());
+    }
 }
@@ -0,0 +1,501 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use num_traits_plus::float_plus::*;
+
+use crate::{
+    cie::apply_matrix, clamped, linear_rgb::LinearRGB, ColourComponent, Degrees, HueConstants,
+    RGBConstants, RGB,
+};
+
+// Matrices as published by Björn Ottosson (with the precision used by CSS Color 4)
+const LINEAR_SRGB_TO_LMS: [[f64; 3]; 3] = [
+    [
+        0.412_221_469_470_762_9,
+        0.536_332_537_261_734_9,
+        0.051_445_993_267_502_2,
+    ],
+    [
+        0.211_903_495_817_825_1,
+        0.680_699_550_645_234_4,
+        0.107_396_953_536_940_6,
+    ],
+    [
+        0.088_302_459_190_056_4,
+        0.281_718_839_136_121_5,
+        0.629_978_701_673_822_2,
+    ],
+];
+
+const LMS_TO_OKLAB: [[f64; 3]; 3] = [
+    [
+        0.210_454_268_309_314,
+        0.793_617_774_702_305_4,
+        -0.004_072_043_011_619_3,
+    ],
+    [
+        1.977_998_532_431_168_4,
+        -2.428_592_242_048_58,
+        0.450_593_709_617_411,
+    ],
+    [
+        0.025_904_042_465_547_8,
+        0.782_771_712_457_529_6,
+        -0.808_675_754_923_077_4,
+    ],
+];
+
+const OKLAB_TO_LMS: [[f64; 3]; 3] = [
+    [1.0, 0.396_337_777_376_174_9, 0.215_803_757_309_913_6],
+    [1.0, -0.105_561_345_815_658_6, -0.063_854_172_825_813_3],
+    [1.0, -0.089_484_177_529_811_9, -1.291_485_548_019_409_2],
+];
+
+const LMS_TO_LINEAR_SRGB: [[f64; 3]; 3] = [
+    [
+        4.076_741_636_075_958,
+        -3.307_711_539_258_063,
+        0.230_969_903_182_104_3,
+    ],
+    [
+        -1.268_437_973_285_031_5,
+        2.609_757_349_287_688,
+        -0.341_319_376_002_657,
+    ],
+    [
+        -0.004_196_076_138_675_6,
+        -0.703_418_617_935_936_2,
+        1.707_614_694_074_611_7,
+    ],
+];
+
+/// Björn Ottosson's perceptual Oklab colour space (L in the range 0.0 to 1.0).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklab<F: ColourComponent> {
+    pub(crate) l: F,
+    pub(crate) a: F,
+    pub(crate) b: F,
+}
+
+impl<F: ColourComponent> Oklab<F> {
+    pub fn l(&self) -> F {
+        self.l
+    }
+
+    pub fn a(&self) -> F {
+        self.a
+    }
+
+    pub fn b(&self) -> F {
+        self.b
+    }
+
+    // Linear sRGB components without any clamping.
+    pub(crate) fn linear_rgb_array(&self) -> [F; 3] {
+        let lms_ = apply_matrix(&OKLAB_TO_LMS, [self.l, self.a, self.b]);
+        apply_matrix(
+            &LMS_TO_LINEAR_SRGB,
+            [lms_[0].powi(3), lms_[1].powi(3), lms_[2].powi(3)],
+        )
+    }
+
+    /// Whether this colour can be displayed in sRGB (allowing for rounding errors).
+    pub fn is_in_gamut(&self) -> bool {
+        let tolerance = F::from(0.000_001).unwrap();
+        self.linear_rgb_array()
+            .iter()
+            .all(|c| *c >= -tolerance && *c <= F::ONE + tolerance)
+    }
+}
+
+/// Cylindrical form of Oklab with the hue angle in degrees (0.0 to 360.0).
+/// The hue of a grey is reported as 0.0.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklch<F: ColourComponent> {
+    pub(crate) l: F,
+    pub(crate) c: F,
+    pub(crate) h: F,
+}
+
+impl<F: ColourComponent> Oklch<F> {
+    pub fn l(&self) -> F {
+        self.l
+    }
+
+    pub fn c(&self) -> F {
+        self.c
+    }
+
+    pub fn h(&self) -> F {
+        self.h
+    }
+
+    pub fn hue_angle(&self) -> Degrees<F> {
+        Degrees::from(self.h)
+    }
+
+    pub fn is_in_gamut(&self) -> bool {
+        Oklab::from(self).is_in_gamut()
+    }
+
+    /// The largest chroma, for this colour's lightness and hue, that can be
+    /// reached from grey without leaving the sRGB gamut.
+    pub fn max_chroma_in_gamut(&self) -> F {
+        if self.l <= F::ZERO || self.l >= F::ONE {
+            return F::ZERO;
+        }
+        // The gamut is not convex in Oklch so step out from the grey axis
+        // before refining the boundary.
+        let step = F::from(1.0 / 256.0).unwrap();
+        let mut probe = *self;
+        probe.c = F::ZERO;
+        while probe.c < F::ONE {
+            probe.c += step;
+            if !probe.is_in_gamut() {
+                break;
+            }
+        }
+        let mut high = probe.c;
+        let mut low = high - step;
+        // 32 iterations gets well below the precision of either float type
+        for _ in 0..32 {
+            probe.c = (low + high) * F::HALF;
+            if probe.is_in_gamut() {
+                low = probe.c;
+            } else {
+                high = probe.c;
+            }
+        }
+        low
+    }
+}
+
+macro_rules! impl_oklab_constants {
+    ( $f:ty ) => {
+        impl HueConstants for Oklab<$f> {
+            const RED: Self = Self {
+                l: 0.627_955_363_921_431_1_f64 as $f,
+                a: 0.224_863_068_426_274_2_f64 as $f,
+                b: 0.125_846_277_330_584_9_f64 as $f,
+            };
+            const GREEN: Self = Self {
+                l: 0.866_439_617_523_436_8_f64 as $f,
+                a: -0.233_887_580_936_557_7_f64 as $f,
+                b: 0.179_498_445_160_937_6_f64 as $f,
+            };
+            const BLUE: Self = Self {
+                l: 0.452_013_718_174_423_7_f64 as $f,
+                a: -0.032_456_975_170_797_93_f64 as $f,
+                b: -0.311_528_165_677_577_74_f64 as $f,
+            };
+
+            const CYAN: Self = Self {
+                l: 0.905_399_236_013_360_8_f64 as $f,
+                a: -0.149_443_943_508_228_6_f64 as $f,
+                b: -0.039_398_192_151_822_053_f64 as $f,
+            };
+            const MAGENTA: Self = Self {
+                l: 0.701_673_859_101_741_3_f64 as $f,
+                a: 0.274_566_305_727_562_8_f64 as $f,
+                b: -0.169_156_084_461_378_53_f64 as $f,
+            };
+            const YELLOW: Self = Self {
+                l: 0.967_982_726_792_799_f64 as $f,
+                a: -0.071_369_082_918_236_63_f64 as $f,
+                b: 0.198_569_717_631_680_96_f64 as $f,
+            };
+        }
+
+        impl RGBConstants for Oklab<$f> {
+            const WHITE: Self = Self {
+                l: 1.0,
+                a: 0.0,
+                b: 0.0,
+            };
+            const BLACK: Self = Self {
+                l: 0.0,
+                a: 0.0,
+                b: 0.0,
+            };
+        }
+
+        impl HueConstants for Oklch<$f> {
+            const RED: Self = Self {
+                l: 0.627_955_363_921_431_1_f64 as $f,
+                c: 0.257_683_303_805_360_6_f64 as $f,
+                h: 29.233_880_279_627_865_f64 as $f,
+            };
+            const GREEN: Self = Self {
+                l: 0.866_439_617_523_436_8_f64 as $f,
+                c: 0.294_827_224_542_695_44_f64 as $f,
+                h: 142.495_345_041_443_87_f64 as $f,
+            };
+            const BLUE: Self = Self {
+                l: 0.452_013_718_174_423_7_f64 as $f,
+                c: 0.313_214_388_634_484_86_f64 as $f,
+                h: 264.052_022_616_369_87_f64 as $f,
+            };
+
+            const CYAN: Self = Self {
+                l: 0.905_399_236_013_360_8_f64 as $f,
+                c: 0.154_550_023_604_406_2_f64 as $f,
+                h: 194.768_959_897_981_77_f64 as $f,
+            };
+            const MAGENTA: Self = Self {
+                l: 0.701_673_859_101_741_3_f64 as $f,
+                c: 0.322_490_987_705_372_16_f64 as $f,
+                h: 328.363_415_174_990_17_f64 as $f,
+            };
+            const YELLOW: Self = Self {
+                l: 0.967_982_726_792_799_f64 as $f,
+                c: 0.211_005_873_749_797_87_f64 as $f,
+                h: 109.769_236_128_166_14_f64 as $f,
+            };
+        }
+
+        impl RGBConstants for Oklch<$f> {
+            const WHITE: Self = Self {
+                l: 1.0,
+                c: 0.0,
+                h: 0.0,
+            };
+            const BLACK: Self = Self {
+                l: 0.0,
+                c: 0.0,
+                h: 0.0,
+            };
+        }
+    };
+}
+
+impl_oklab_constants!(f32);
+impl_oklab_constants!(f64);
+
+impl<F: ColourComponent> FloatApproxEq<F> for Oklab<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.l.approx_eq(&other.l, max_diff)
+            && self.a.approx_eq(&other.a, max_diff)
+            && self.b.approx_eq(&other.b, max_diff)
+    }
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for Oklch<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.l.approx_eq(&other.l, max_diff)
+            && self.c.approx_eq(&other.c, max_diff)
+            && self.h.approx_eq(&other.h, max_diff)
+    }
+}
+
+impl<F: ColourComponent> From<[F; 3]> for Oklab<F> {
+    fn from(array: [F; 3]) -> Self {
+        Self {
+            l: array[0],
+            a: array[1],
+            b: array[2],
+        }
+    }
+}
+
+impl<F: ColourComponent> From<[F; 3]> for Oklch<F> {
+    fn from(array: [F; 3]) -> Self {
+        debug_assert!(array[1] >= F::ZERO, "{:?}", array);
+        Self {
+            l: array[0],
+            c: array[1],
+            h: array[2],
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&LinearRGB<F>> for Oklab<F> {
+    fn from(linear_rgb: &LinearRGB<F>) -> Self {
+        let [red, green, blue] = linear_rgb.0;
+        if red == green && red == blue {
+            // Avoid rounding errors giving greys a hue
+            return Self {
+                l: red.cbrt(),
+                a: F::ZERO,
+                b: F::ZERO,
+            };
+        }
+        let lms = apply_matrix(&LINEAR_SRGB_TO_LMS, linear_rgb.0);
+        apply_matrix(&LMS_TO_OKLAB, [lms[0].cbrt(), lms[1].cbrt(), lms[2].cbrt()]).into()
+    }
+}
+
+/// Colours outside the sRGB gamut are clipped to it.
+impl<F: ColourComponent> From<&Oklab<F>> for LinearRGB<F> {
+    fn from(oklab: &Oklab<F>) -> Self {
+        if oklab.a == F::ZERO && oklab.b == F::ZERO {
+            let value = clamped(oklab.l.powi(3));
+            return [value, value, value].into();
+        }
+        let mut array = oklab.linear_rgb_array();
+        for component in array.iter_mut() {
+            *component = clamped(*component);
+        }
+        array.into()
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for Oklab<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        (&LinearRGB::from(rgb)).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for Oklab<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&Oklab<F>> for RGB<F> {
+    fn from(oklab: &Oklab<F>) -> Self {
+        LinearRGB::from(oklab).into()
+    }
+}
+
+impl<F: ColourComponent> From<Oklab<F>> for RGB<F> {
+    fn from(oklab: Oklab<F>) -> Self {
+        (&oklab).into()
+    }
+}
+
+impl<F: ColourComponent> From<&Oklab<F>> for Oklch<F> {
+    fn from(oklab: &Oklab<F>) -> Self {
+        let c = oklab.a.hypot(oklab.b);
+        let h = if c == F::ZERO {
+            F::ZERO
+        } else {
+            let degrees = oklab.b.atan2(oklab.a).to_degrees();
+            if degrees < F::ZERO {
+                degrees + F::DEG_360
+            } else {
+                degrees
+            }
+        };
+        Self { l: oklab.l, c, h }
+    }
+}
+
+impl<F: ColourComponent> From<&Oklch<F>> for Oklab<F> {
+    fn from(oklch: &Oklch<F>) -> Self {
+        let radians = oklch.h.to_radians();
+        Self {
+            l: oklch.l,
+            a: oklch.c * radians.cos(),
+            b: oklch.c * radians.sin(),
+        }
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for Oklch<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        (&Oklab::from(rgb)).into()
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for Oklch<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+impl<F: ColourComponent> From<&Oklch<F>> for RGB<F> {
+    fn from(oklch: &Oklch<F>) -> Self {
+        Oklab::from(oklch).into()
+    }
+}
+
+impl<F: ColourComponent> From<Oklch<F>> for RGB<F> {
+    fn from(oklch: Oklch<F>) -> Self {
+        (&oklch).into()
+    }
+}
+
+impl_colour_interface_via_rgb!(Oklab, {
+    fn oklab(&self) -> Oklab<F> {
+        *self
+    }
+
+    fn oklch(&self) -> Oklch<F> {
+        self.into()
+    }
+});
+
+impl_colour_interface_via_rgb!(Oklch, {
+    fn oklab(&self) -> Oklab<F> {
+        self.into()
+    }
+
+    fn oklch(&self) -> Oklch<F> {
+        *self
+    }
+});
+
+#[cfg(test)]
+mod oklab_tests {
+    use super::*;
+    use crate::ColourInterface;
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn constants_match_conversions() {
+        for (rgb, oklab) in RGB::<f64>::PRIMARIES
+            .iter()
+            .zip(Oklab::<f64>::PRIMARIES.iter())
+        {
+            assert_approx_eq!(Oklab::from(rgb), *oklab, 0.000_000_001);
+        }
+        for (rgb, oklch) in RGB::<f64>::SECONDARIES
+            .iter()
+            .zip(Oklch::<f64>::SECONDARIES.iter())
+        {
+            assert_approx_eq!(Oklch::from(rgb), *oklch, 0.000_000_001);
+        }
+        for (rgb, oklab) in RGB::<f64>::GREYS.iter().zip(Oklab::<f64>::GREYS.iter()) {
+            assert_approx_eq!(Oklab::from(rgb), *oklab, 0.000_000_001);
+        }
+        assert_approx_eq!(Oklab::from(RGB::<f32>::BLUE), Oklab::<f32>::BLUE, 0.000_01);
+    }
+
+    #[test]
+    fn round_trips() {
+        let values = [0.0, 0.001, 0.01, 0.2, 0.5, 0.8, 0.99, 1.0];
+        for red in values.iter() {
+            for green in values.iter() {
+                for blue in values.iter() {
+                    let rgb = RGB::<f64>::from([*red, *green, *blue]);
+                    assert!(rgb.oklab().is_in_gamut());
+                    assert_approx_eq!(RGB::from(Oklab::from(rgb)), rgb, 0.000_000_001);
+                    assert_approx_eq!(RGB::from(Oklch::from(rgb)), rgb, 0.000_000_001);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gamut_limits() {
+        assert!(!Oklch::<f64>::from([0.5, 0.4, 30.0]).is_in_gamut());
+        for oklch in Oklch::<f64>::PRIMARIES
+            .iter()
+            .chain(Oklch::<f64>::SECONDARIES.iter())
+        {
+            let max_chroma = oklch.max_chroma_in_gamut();
+            assert!(max_chroma <= oklch.c() + 0.000_001);
+            let mut probe = *oklch;
+            probe.c = max_chroma;
+            assert!(probe.is_in_gamut());
+            probe.c = max_chroma + 0.001;
+            assert!(!probe.is_in_gamut());
+        }
+        assert_approx_eq!(
+            Oklch::<f64>::RED.max_chroma_in_gamut(),
+            Oklch::<f64>::RED.c(),
+            0.000_01
+        );
+        assert_eq!(Oklch::<f64>::WHITE.max_chroma_in_gamut(), 0.0);
+        assert_eq!(Oklch::<f64>::BLACK.max_chroma_in_gamut(), 0.0);
+    }
+}
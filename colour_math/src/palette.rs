@@ -0,0 +1,480 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Named colour palettes and GIMP `.gpl` palette files.  Binary Adobe palette
+//! files are handled by the `ase` and `aco` submodules.
+
+pub mod aco;
+pub mod ase;
+
+use std::{
+    convert::TryFrom,
+    io::{Read, Write},
+};
+
+use crate::{
+    attributes::hue_wheel::{ColouredShape, Shape, ShapeConsts},
+    clamped,
+    cmyk::CMYK,
+    css::{CSSColour, CSSFormat},
+    ColourComponent, RGB, RGB8,
+};
+
+#[derive(Debug)]
+pub enum PaletteError {
+    IOError(std::io::Error),
+    MalformedText(String),
+    MalformedData(String),
+    UnsupportedEntry(String),
+    TooLarge(String),
+}
+
+impl std::fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaletteError::IOError(error) => write!(f, "Palette I/O error: {}", error),
+            PaletteError::MalformedText(string) => write!(f, "Malformed palette: {}", string),
+            PaletteError::MalformedData(string) => write!(f, "Malformed palette data: {}", string),
+            PaletteError::UnsupportedEntry(string) => {
+                write!(f, "Unsupported palette entry: {}", string)
+            }
+            PaletteError::TooLarge(string) => write!(f, "Palette too large: {}", string),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+impl From<std::io::Error> for PaletteError {
+    fn from(error: std::io::Error) -> Self {
+        PaletteError::IOError(error)
+    }
+}
+
+const GPL_MAGIC: &str = "GIMP Palette";
+
+/// A palette colour together with its name and the comment lines that preceded it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PaletteEntry {
+    pub(crate) rgb: RGB8,
+    pub(crate) name: String,
+    pub(crate) comments: Vec<String>,
+}
+
+impl PaletteEntry {
+    pub fn new(rgb: RGB8, name: &str) -> Self {
+        Self {
+            rgb,
+            name: name.to_string(),
+            comments: vec![],
+        }
+    }
+
+    pub fn rgb(&self) -> RGB8 {
+        self.rgb
+    }
+
+    /// May be empty as names are optional in palette files.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The text (after the `#`) of the comment lines immediately preceding this entry.
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+}
+
+/// A named colour as read from, or to be written to, a binary palette file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Swatch<F: ColourComponent> {
+    pub(crate) name: String,
+    pub(crate) rgb: RGB<F>,
+}
+
+impl<F: ColourComponent> Swatch<F> {
+    pub fn new(rgb: RGB<F>, name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rgb,
+        }
+    }
+
+    pub fn rgb(&self) -> RGB<F> {
+        self.rgb
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A named list of colours.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Palette {
+    pub(crate) name: String,
+    pub(crate) columns: Option<usize>,
+    pub(crate) comments: Vec<String>,
+    pub(crate) entries: Vec<PaletteEntry>,
+    pub(crate) trailing_comments: Vec<String>,
+}
+
+impl Palette {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// The number of columns that editors should use when displaying the palette.
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn columns(&self) -> Option<usize> {
+        self.columns
+    }
+
+    /// The text (after the `#`) of the comment lines preceding the first entry.
+    pub fn comments(&self) -> &[String] {
+        &self.comments
+    }
+
+    /// The text (after the `#`) of the comment lines following the last entry.
+    pub fn trailing_comments(&self) -> &[String] {
+        &self.trailing_comments
+    }
+
+    pub fn entries(&self) -> &[PaletteEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn add_comment(&mut self, comment: &str) {
+        self.comments.push(comment.to_string());
+    }
+
+    pub fn add_entry(&mut self, rgb: RGB8, name: &str) {
+        self.entries.push(PaletteEntry::new(rgb, name));
+    }
+
+    pub fn from_swatches<F: ColourComponent>(name: &str, swatches: &[Swatch<F>]) -> Self {
+        let mut palette = Self::new(name);
+        for swatch in swatches.iter() {
+            palette.add_entry(RGB8::from(swatch.rgb), &swatch.name);
+        }
+        palette
+    }
+
+    pub fn swatches<F: ColourComponent>(&self) -> Vec<Swatch<F>> {
+        self.entries
+            .iter()
+            .map(|entry| Swatch::new(RGB::from(entry.rgb), &entry.name))
+            .collect()
+    }
+
+    /// Shapes (identified by palette name and entry index) for display on a `HueWheel`.
+    pub fn coloured_shapes<F: ColourComponent + ShapeConsts>(
+        &self,
+        shape: Shape,
+    ) -> Vec<ColouredShape<F>> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let hex = entry.rgb.css_string(CSSFormat::Hex);
+                let tooltip_text = if entry.name.is_empty() {
+                    hex
+                } else {
+                    format!("{}\n{}", entry.name, hex)
+                };
+                ColouredShape::new(
+                    RGB::<F>::from(entry.rgb),
+                    &format!("{}:{}", self.name, index),
+                    &tooltip_text,
+                    shape,
+                )
+            })
+            .collect()
+    }
+}
+
+fn grey_rgb<F: ColourComponent>(value: F) -> RGB<F> {
+    let value = clamped(value);
+    RGB::from([value, value, value])
+}
+
+fn cmyk_rgb<F: ColourComponent>(cyan: F, magenta: F, yellow: F, black: F) -> RGB<F> {
+    CMYK::from([
+        clamped(cyan),
+        clamped(magenta),
+        clamped(yellow),
+        clamped(black),
+    ])
+    .into()
+}
+
+/// Big endian reading of the binary palette formats.
+struct BinaryReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> BinaryReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn bytes(&mut self, count: usize) -> Result<&'a [u8], PaletteError> {
+        if count > self.data.len() {
+            return Err(PaletteError::MalformedData(
+                "unexpected end of data".to_string(),
+            ));
+        }
+        let (bytes, rest) = self.data.split_at(count);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, PaletteError> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, PaletteError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, PaletteError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    /// A UTF-16 string of `units` code units (including any terminating null).
+    fn utf16(&mut self, units: usize) -> Result<String, PaletteError> {
+        let mut code_units = (0..units)
+            .map(|_| self.u16())
+            .collect::<Result<Vec<u16>, PaletteError>>()?;
+        while code_units.last() == Some(&0) {
+            code_units.pop();
+        }
+        String::from_utf16(&code_units)
+            .map_err(|_| PaletteError::MalformedData("invalid UTF-16 name".to_string()))
+    }
+}
+
+/// `value` as a `u16` (or an error describing `what` if it doesn't fit).
+fn checked_u16(what: &str, value: usize) -> Result<u16, PaletteError> {
+    u16::try_from(value).map_err(|_| PaletteError::TooLarge(format!("{}: {}", what, value)))
+}
+
+/// `value` as a `u32` (or an error describing `what` if it doesn't fit).
+fn checked_u32(what: &str, value: usize) -> Result<u32, PaletteError> {
+    u32::try_from(value).map_err(|_| PaletteError::TooLarge(format!("{}: {}", what, value)))
+}
+
+/// The number of UTF-16 code units `push_utf16()` writes for `name`.
+fn utf16_length(name: &str) -> usize {
+    name.encode_utf16().count() + 1
+}
+
+/// Append `name` as null terminated UTF-16 (big endian) code units.
+fn push_utf16(data: &mut Vec<u8>, name: &str) {
+    for unit in name.encode_utf16().chain(std::iter::once(0)) {
+        data.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+fn parse_gpl_entry(line: &str) -> Option<PaletteEntry> {
+    let mut rest = line.trim();
+    let mut components = [0_u8; 3];
+    for component in components.iter_mut() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        *component = rest[..end].parse().ok()?;
+        rest = rest[end..].trim_start();
+    }
+    Some(PaletteEntry::new(RGB8::from(components), rest))
+}
+
+impl Palette {
+    pub fn from_gpl_str(text: &str) -> Result<Self, PaletteError> {
+        let mut lines = text
+            .lines()
+            .map(|line| line.trim_end_matches('\r'))
+            .skip_while(|line| line.trim().is_empty());
+        match lines.next() {
+            Some(line) if line.trim_start_matches('\u{feff}').trim() == GPL_MAGIC => (),
+            _ => {
+                return Err(PaletteError::MalformedText(format!(
+                    "missing \"{}\" header",
+                    GPL_MAGIC
+                )))
+            }
+        }
+        let mut palette = Palette::default();
+        let mut comments = vec![];
+        for line in lines {
+            if let Some(comment) = line.trim_start().strip_prefix('#') {
+                comments.push(comment.to_string());
+            } else if line.trim().is_empty() {
+                continue;
+            } else if let Some(name) = line.strip_prefix("Name:") {
+                palette.name = name.trim().to_string();
+            } else if let Some(columns) = line.strip_prefix("Columns:") {
+                let columns = columns.trim().parse::<usize>().map_err(|_| {
+                    PaletteError::MalformedText(format!("bad column count: {}", line))
+                })?;
+                palette.columns = Some(columns);
+            } else if let Some(mut entry) = parse_gpl_entry(line) {
+                if palette.entries.is_empty() {
+                    palette.comments.append(&mut comments);
+                } else {
+                    entry.comments.append(&mut comments);
+                }
+                palette.entries.push(entry);
+            } else {
+                return Err(PaletteError::MalformedText(format!("bad entry: {}", line)));
+            }
+        }
+        if palette.entries.is_empty() {
+            palette.comments = comments;
+        } else {
+            palette.trailing_comments = comments;
+        }
+        Ok(palette)
+    }
+
+    pub fn to_gpl_string(&self) -> String {
+        let mut string = format!("{}\n", GPL_MAGIC);
+        if !self.name.is_empty() {
+            string += &format!("Name: {}\n", self.name);
+        }
+        if let Some(columns) = self.columns {
+            string += &format!("Columns: {}\n", columns);
+        }
+        let add_comments = |string: &mut String, comments: &[String]| {
+            for comment in comments.iter() {
+                *string += &format!("#{}\n", comment);
+            }
+        };
+        add_comments(&mut string, &self.comments);
+        for entry in self.entries.iter() {
+            add_comments(&mut string, &entry.comments);
+            let [red, green, blue] = <[u8; 3]>::from(&entry.rgb);
+            string += &format!("{:3} {:3} {:3}", red, green, blue);
+            if !entry.name.is_empty() {
+                string += &format!("\t{}", entry.name);
+            }
+            string.push('\n');
+        }
+        add_comments(&mut string, &self.trailing_comments);
+        string
+    }
+
+    pub fn read_gpl<R: Read>(mut reader: R) -> Result<Self, PaletteError> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Self::from_gpl_str(&text)
+    }
+
+    pub fn write_gpl<W: Write>(&self, mut writer: W) -> Result<(), PaletteError> {
+        writer.write_all(self.to_gpl_string().as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod palette_tests {
+    use super::*;
+    use crate::HueConstants;
+
+    const GPL_TEXT: &str = "GIMP Palette
+Name: Test Palette
+Columns: 4
+# A header comment
+#
+  0   0   0\tBlack
+255 255 255\tWhite
+# before cornflower
+100 149 237\tCornflower Blue
+ 12  34  56
+# trailing
+";
+
+    #[test]
+    fn gpl_round_trip() {
+        let palette = Palette::from_gpl_str(GPL_TEXT).unwrap();
+        assert_eq!(palette.name(), "Test Palette");
+        assert_eq!(palette.columns(), Some(4));
+        assert_eq!(palette.comments(), &[" A header comment", ""]);
+        assert_eq!(palette.len(), 4);
+        assert_eq!(palette.entries()[1].rgb(), RGB8::from([255, 255, 255]));
+        assert_eq!(palette.entries()[2].name(), "Cornflower Blue");
+        assert_eq!(palette.entries()[2].comments(), &[" before cornflower"]);
+        assert_eq!(palette.entries()[3].name(), "");
+        assert_eq!(palette.trailing_comments(), &[" trailing"]);
+        assert_eq!(palette.to_gpl_string(), GPL_TEXT);
+        let mut buffer = vec![];
+        palette.write_gpl(&mut buffer).unwrap();
+        assert_eq!(Palette::read_gpl(buffer.as_slice()).unwrap(), palette);
+    }
+
+    #[test]
+    fn gpl_lenient_parsing() {
+        let text =
+            "\u{feff}GIMP Palette\r\nName:Loose\r\n\r\n1 2 3    Spaced   name \r\n\t4\t5\t6\r\n";
+        let palette = Palette::from_gpl_str(text).unwrap();
+        assert_eq!(palette.name(), "Loose");
+        assert_eq!(palette.columns(), None);
+        assert_eq!(palette.entries()[0].name(), "Spaced   name");
+        assert_eq!(palette.entries()[1].rgb(), RGB8::from([4, 5, 6]));
+        for bad in [
+            "",
+            "Name: No header\n",
+            "GIMP Palette\n256 0 0 Too bright\n",
+            "GIMP Palette\n1 2\n",
+            "GIMP Palette\nColumns: many\n",
+        ]
+        .iter()
+        {
+            assert!(Palette::from_gpl_str(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn palette_construction() {
+        let mut palette = Palette::new("Built").with_columns(2);
+        palette.add_comment(" made in code");
+        palette.add_entry(RGB8::from([255, 0, 0]), "Red");
+        palette.add_entry(RGB8::from([0, 0, 0]), "");
+        assert_eq!(
+            palette.to_gpl_string(),
+            "GIMP Palette\nName: Built\nColumns: 2\n# made in code\n255   0   0\tRed\n  0   0   0\n"
+        );
+        let swatches = palette.swatches::<f64>();
+        assert_eq!(swatches[0], Swatch::new(RGB::RED, "Red"));
+        assert_eq!(
+            Palette::from_swatches("Built", &swatches).entries(),
+            palette.entries()
+        );
+        let shapes = palette.coloured_shapes::<f64>(Shape::Circle);
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].id(), "Built:0");
+        assert_eq!(shapes[1].id(), "Built:1");
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Photoshop colour swatch (`.aco`) files.  Swatches are written as RGB entries in a
+//! version 1 section followed by a version 2 section containing the names.
+
+use std::io::{Read, Write};
+
+use super::{
+    checked_u16, checked_u32, cmyk_rgb, grey_rgb, push_utf16, utf16_length, BinaryReader,
+    PaletteError, Swatch,
+};
+use crate::{cie::Lab, f, hsv::HSV, ColourComponent, Illuminant, RGB};
+
+const RGB_SPACE: u16 = 0;
+const HSB_SPACE: u16 = 1;
+const CMYK_SPACE: u16 = 2;
+const LAB_SPACE: u16 = 7;
+const GRAYSCALE_SPACE: u16 = 8;
+
+fn proportion<F: ColourComponent>(value: u16) -> F {
+    f::<F>(value as f64) / f(65535.0)
+}
+
+fn rgb_for_colour_data<F: ColourComponent>(
+    colour_space: u16,
+    data: [u16; 4],
+) -> Result<RGB<F>, PaletteError> {
+    let rgb = match colour_space {
+        RGB_SPACE => RGB::from([
+            proportion(data[0]),
+            proportion(data[1]),
+            proportion(data[2]),
+        ]),
+        HSB_SPACE => HSV::from([
+            proportion::<F>(data[0]) * f(360.0),
+            proportion(data[1]),
+            proportion(data[2]),
+        ])
+        .into(),
+        // 0 is full ink and 65535 no ink
+        CMYK_SPACE => cmyk_rgb(
+            F::ONE - proportion(data[0]),
+            F::ONE - proportion(data[1]),
+            F::ONE - proportion(data[2]),
+            F::ONE - proportion(data[3]),
+        ),
+        // L is in hundredths of a percent and a and b are signed hundredths
+        LAB_SPACE => Lab::from([
+            f::<F>(data[0] as f64) / f(100.0),
+            f::<F>(data[1] as i16 as f64) / f(100.0),
+            f::<F>(data[2] as i16 as f64) / f(100.0),
+        ])
+        .rgb_with_white(Illuminant::D50),
+        // the amount of black ink in hundredths of a percent
+        GRAYSCALE_SPACE => grey_rgb(F::ONE - f::<F>(data[0] as f64) / f(10000.0)),
+        _ => {
+            return Err(PaletteError::UnsupportedEntry(format!(
+                "ACO colour space {}",
+                colour_space
+            )))
+        }
+    };
+    Ok(rgb)
+}
+
+fn read_colour<F: ColourComponent>(input: &mut BinaryReader) -> Result<RGB<F>, PaletteError> {
+    let colour_space = input.u16()?;
+    let mut data = [0_u16; 4];
+    for value in data.iter_mut() {
+        *value = input.u16()?;
+    }
+    rgb_for_colour_data(colour_space, data)
+}
+
+/// Read the colours of an `.aco` file (with their names if it has a version 2 section).
+/// RGB, HSB, CMYK, Lab (D50) and grayscale entries are converted to RGB and any other
+/// colour space is reported as unsupported.
+pub fn read_swatches<F: ColourComponent, R: Read>(
+    mut reader: R,
+) -> Result<Vec<Swatch<F>>, PaletteError> {
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+    let mut input = BinaryReader::new(&data);
+    let mut swatches = vec![];
+    let mut version = input.u16()?;
+    if version == 1 {
+        let count = input.u16()?;
+        for _ in 0..count {
+            swatches.push(Swatch::new(read_colour(&mut input)?, ""));
+        }
+        if input.is_empty() {
+            return Ok(swatches);
+        }
+        version = input.u16()?;
+    }
+    if version != 2 {
+        return Err(PaletteError::MalformedData(format!(
+            "unexpected ACO version {}",
+            version
+        )));
+    }
+    // the version 2 section repeats the colours with their names
+    swatches.clear();
+    let count = input.u16()?;
+    for _ in 0..count {
+        let rgb = read_colour(&mut input)?;
+        let name_length = input.u32()? as usize;
+        let name = input.utf16(name_length)?;
+        swatches.push(Swatch { name, rgb });
+    }
+    Ok(swatches)
+}
+
+pub fn write_swatches<F: ColourComponent, W: Write>(
+    swatches: &[Swatch<F>],
+    mut writer: W,
+) -> Result<(), PaletteError> {
+    let push_colour = |data: &mut Vec<u8>, rgb: &RGB<F>| {
+        data.extend_from_slice(&RGB_SPACE.to_be_bytes());
+        for value in rgb.0.iter() {
+            let value = (*value * f(65535.0)).round().to_u16().unwrap();
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+        data.extend_from_slice(&0_u16.to_be_bytes());
+    };
+    let count = checked_u16("ACO swatch count", swatches.len())?;
+    let mut data = vec![];
+    data.extend_from_slice(&1_u16.to_be_bytes());
+    data.extend_from_slice(&count.to_be_bytes());
+    for swatch in swatches.iter() {
+        push_colour(&mut data, &swatch.rgb);
+    }
+    data.extend_from_slice(&2_u16.to_be_bytes());
+    data.extend_from_slice(&count.to_be_bytes());
+    for swatch in swatches.iter() {
+        push_colour(&mut data, &swatch.rgb);
+        let name_length = checked_u32("ACO name length", utf16_length(&swatch.name))?;
+        data.extend_from_slice(&name_length.to_be_bytes());
+        push_utf16(&mut data, &swatch.name);
+    }
+    writer.write_all(&data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod aco_tests {
+    use super::*;
+    use crate::{HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    fn version_1(entries: &[(u16, [u16; 4])]) -> Vec<u8> {
+        let mut data = 1_u16.to_be_bytes().to_vec();
+        data.extend_from_slice(&(entries.len() as u16).to_be_bytes());
+        for (colour_space, values) in entries.iter() {
+            data.extend_from_slice(&colour_space.to_be_bytes());
+            for value in values.iter() {
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn aco_round_trip() {
+        let swatches = vec![
+            Swatch::new(RGB::<f64>::RED, "Red"),
+            Swatch::new(RGB::from([0.2, 0.4, 0.6]), "Bleu ciel é"),
+            Swatch::new(RGB::BLACK, ""),
+        ];
+        let mut data = vec![];
+        write_swatches(&swatches, &mut data).unwrap();
+        let read = read_swatches::<f64, _>(data.as_slice()).unwrap();
+        assert_eq!(read.len(), swatches.len());
+        for (read, swatch) in read.iter().zip(swatches.iter()) {
+            assert_eq!(read.name(), swatch.name());
+            assert_approx_eq!(read.rgb(), swatch.rgb(), 0.000_01);
+        }
+        // the version 1 section alone gives the colours without names
+        let version_1_length = 4 + swatches.len() * 10;
+        let read = read_swatches::<f64, _>(&data[..version_1_length]).unwrap();
+        assert_eq!(read.len(), swatches.len());
+        assert_eq!(read[0].name(), "");
+        assert_approx_eq!(read[0].rgb(), RGB::RED);
+    }
+
+    #[test]
+    fn aco_colour_spaces() {
+        let data = version_1(&[
+            (HSB_SPACE, [21845, 65535, 65535, 0]),
+            (CMYK_SPACE, [0, 65535, 65535, 65535]),
+            (CMYK_SPACE, [65535, 65535, 65535, 32768]),
+            (LAB_SPACE, [10000, 0, 0, 0]),
+            (LAB_SPACE, [5000, 2000, -3000_i16 as u16, 0]),
+            (GRAYSCALE_SPACE, [2500, 0, 0, 0]),
+        ]);
+        let swatches = read_swatches::<f64, _>(data.as_slice()).unwrap();
+        assert_approx_eq!(swatches[0].rgb(), RGB::GREEN, 0.000_01);
+        assert_approx_eq!(swatches[1].rgb(), RGB::CYAN);
+        assert_approx_eq!(swatches[2].rgb(), RGB::from([0.5, 0.5, 0.5]), 0.000_1);
+        assert_approx_eq!(swatches[3].rgb(), RGB::WHITE, 0.001);
+        assert_approx_eq!(
+            swatches[4].rgb(),
+            Lab::from([50.0, 20.0, -30.0]).rgb_with_white(Illuminant::D50),
+            0.000_001
+        );
+        assert_approx_eq!(swatches[5].rgb(), RGB::from([0.75, 0.75, 0.75]));
+    }
+
+    #[test]
+    fn aco_errors() {
+        let data = version_1(&[(RGB_SPACE, [0, 0, 0, 0]), (10, [0, 0, 0, 0])]);
+        match read_swatches::<f64, _>(data.as_slice()) {
+            Err(PaletteError::UnsupportedEntry(text)) => assert!(text.contains("10")),
+            result => panic!("unexpected: {:?}", result),
+        }
+        let data = version_1(&[(RGB_SPACE, [0, 0, 0, 0])]);
+        assert!(matches!(
+            read_swatches::<f64, _>(&data[..data.len() - 1]),
+            Err(PaletteError::MalformedData(_))
+        ));
+        assert!(matches!(
+            read_swatches::<f64, _>(&[0_u8, 3, 0, 0][..]),
+            Err(PaletteError::MalformedData(_))
+        ));
+        assert!(matches!(
+            read_swatches::<f64, _>(&[][..]),
+            Err(PaletteError::MalformedData(_))
+        ));
+        let swatches = vec![Swatch::new(RGB::<f64>::RED, ""); 65_536];
+        assert!(matches!(
+            write_swatches(&swatches, &mut vec![]),
+            Err(PaletteError::TooLarge(_))
+        ));
+        assert!(write_swatches(&swatches[..65_535], &mut vec![]).is_ok());
+    }
+}
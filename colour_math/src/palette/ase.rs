@@ -0,0 +1,243 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! Adobe Swatch Exchange (`.ase`) files.  Groups are flattened when reading and
+//! swatches are always written as ungrouped RGB entries.
+
+use std::io::{Read, Write};
+
+use super::{
+    checked_u16, checked_u32, cmyk_rgb, grey_rgb, push_utf16, utf16_length, BinaryReader,
+    PaletteError, Swatch,
+};
+use crate::{cie::Lab, clamped, f, ColourComponent, Illuminant, RGB};
+
+const SIGNATURE: &[u8] = b"ASEF";
+const COLOUR_ENTRY: u16 = 0x0001;
+const GROUP_START: u16 = 0xC001;
+const GROUP_END: u16 = 0xC002;
+const NORMAL_COLOUR: u16 = 2;
+
+fn read_colour_entry<F: ColourComponent>(
+    block: &mut BinaryReader,
+) -> Result<Swatch<F>, PaletteError> {
+    let name_length = block.u16()? as usize;
+    let name = block.utf16(name_length)?;
+    let model = block.bytes(4)?;
+    let mut values = |count: usize| -> Result<Vec<F>, PaletteError> {
+        (0..count).map(|_| Ok(f(block.f32()? as f64))).collect()
+    };
+    let rgb = match model {
+        b"RGB " => {
+            let v = values(3)?;
+            RGB::from([clamped(v[0]), clamped(v[1]), clamped(v[2])])
+        }
+        b"Gray" => grey_rgb(values(1)?[0]),
+        b"CMYK" => {
+            let v = values(4)?;
+            cmyk_rgb(v[0], v[1], v[2], v[3])
+        }
+        b"LAB " => {
+            // L is stored as a proportion rather than a percentage
+            let v = values(3)?;
+            Lab::from([v[0] * f(100.0), v[1], v[2]]).rgb_with_white(Illuminant::D50)
+        }
+        _ => {
+            return Err(PaletteError::UnsupportedEntry(format!(
+                "ASE colour model \"{}\" for \"{}\"",
+                String::from_utf8_lossy(model),
+                name
+            )))
+        }
+    };
+    Ok(Swatch { name, rgb })
+}
+
+/// Read the colour entries of an `.ase` file.  RGB, Gray, CMYK and Lab (D50) entries
+/// are converted to RGB and any other colour model is reported as unsupported.
+pub fn read_swatches<F: ColourComponent, R: Read>(
+    mut reader: R,
+) -> Result<Vec<Swatch<F>>, PaletteError> {
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+    let mut input = BinaryReader::new(&data);
+    if input.bytes(SIGNATURE.len()).ok() != Some(SIGNATURE) {
+        return Err(PaletteError::MalformedData(
+            "missing \"ASEF\" signature".to_string(),
+        ));
+    }
+    let major_version = input.u16()?;
+    let _minor_version = input.u16()?;
+    if major_version != 1 {
+        return Err(PaletteError::MalformedData(format!(
+            "unsupported ASE version {}",
+            major_version
+        )));
+    }
+    let block_count = input.u32()?;
+    let mut swatches = vec![];
+    for _ in 0..block_count {
+        let block_type = input.u16()?;
+        let length = input.u32()? as usize;
+        let mut block = BinaryReader::new(input.bytes(length)?);
+        match block_type {
+            COLOUR_ENTRY => swatches.push(read_colour_entry(&mut block)?),
+            GROUP_START | GROUP_END => (),
+            _ => {
+                return Err(PaletteError::MalformedData(format!(
+                    "unknown ASE block type {:#06x}",
+                    block_type
+                )))
+            }
+        }
+    }
+    Ok(swatches)
+}
+
+pub fn write_swatches<F: ColourComponent, W: Write>(
+    swatches: &[Swatch<F>],
+    mut writer: W,
+) -> Result<(), PaletteError> {
+    let mut data = SIGNATURE.to_vec();
+    data.extend_from_slice(&1_u16.to_be_bytes());
+    data.extend_from_slice(&0_u16.to_be_bytes());
+    data.extend_from_slice(&checked_u32("ASE block count", swatches.len())?.to_be_bytes());
+    for swatch in swatches.iter() {
+        let mut block = vec![];
+        let name_length = checked_u16("ASE name length", utf16_length(&swatch.name))?;
+        block.extend_from_slice(&name_length.to_be_bytes());
+        push_utf16(&mut block, &swatch.name);
+        block.extend_from_slice(b"RGB ");
+        for value in swatch.rgb.0.iter() {
+            block.extend_from_slice(&value.to_f32().unwrap().to_be_bytes());
+        }
+        block.extend_from_slice(&NORMAL_COLOUR.to_be_bytes());
+        data.extend_from_slice(&COLOUR_ENTRY.to_be_bytes());
+        data.extend_from_slice(&checked_u32("ASE block length", block.len())?.to_be_bytes());
+        data.append(&mut block);
+    }
+    writer.write_all(&data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod ase_tests {
+    use super::*;
+    use crate::{HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    fn entry(name: &str, model: &[u8], values: &[f32]) -> Vec<u8> {
+        let mut block = vec![];
+        block.extend_from_slice(&(name.encode_utf16().count() as u16 + 1).to_be_bytes());
+        push_utf16(&mut block, name);
+        block.extend_from_slice(model);
+        for value in values.iter() {
+            block.extend_from_slice(&value.to_be_bytes());
+        }
+        block.extend_from_slice(&0_u16.to_be_bytes());
+        let mut data = COLOUR_ENTRY.to_be_bytes().to_vec();
+        data.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        data.append(&mut block);
+        data
+    }
+
+    fn group(block_type: u16, name: &str) -> Vec<u8> {
+        let mut block = vec![];
+        if block_type == GROUP_START {
+            block.extend_from_slice(&(name.encode_utf16().count() as u16 + 1).to_be_bytes());
+            push_utf16(&mut block, name);
+        }
+        let mut data = block_type.to_be_bytes().to_vec();
+        data.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        data.append(&mut block);
+        data
+    }
+
+    fn file(blocks: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = b"ASEF\x00\x01\x00\x00".to_vec();
+        data.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+        for block in blocks.iter() {
+            data.extend_from_slice(block);
+        }
+        data
+    }
+
+    #[test]
+    fn ase_round_trip() {
+        let swatches = vec![
+            Swatch::new(RGB::<f64>::RED, "Red"),
+            Swatch::new(RGB::from([0.2, 0.4, 0.6]), "Bleu ciel é"),
+            Swatch::new(RGB::BLACK, ""),
+        ];
+        let mut data = vec![];
+        write_swatches(&swatches, &mut data).unwrap();
+        assert_eq!(&data[..4], b"ASEF");
+        let read = read_swatches::<f64, _>(data.as_slice()).unwrap();
+        assert_eq!(read.len(), swatches.len());
+        for (read, swatch) in read.iter().zip(swatches.iter()) {
+            assert_eq!(read.name(), swatch.name());
+            assert_approx_eq!(read.rgb(), swatch.rgb(), 0.000_001);
+        }
+    }
+
+    #[test]
+    fn ase_colour_models() {
+        let data = file(&[
+            group(GROUP_START, "Inks"),
+            entry("Grey", b"Gray", &[0.25]),
+            entry("Cyan", b"CMYK", &[1.0, 0.0, 0.0, 0.0]),
+            entry("Dark", b"CMYK", &[0.0, 0.0, 0.0, 0.5]),
+            group(GROUP_END, ""),
+            entry("Paper", b"LAB ", &[1.0, 0.0, 0.0]),
+            entry("Labby", b"LAB ", &[0.5, 20.0, -30.0]),
+        ]);
+        let swatches = read_swatches::<f64, _>(data.as_slice()).unwrap();
+        let names: Vec<&str> = swatches.iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["Grey", "Cyan", "Dark", "Paper", "Labby"]);
+        assert_approx_eq!(swatches[0].rgb(), RGB::from([0.25, 0.25, 0.25]));
+        assert_approx_eq!(swatches[1].rgb(), RGB::CYAN);
+        assert_approx_eq!(swatches[2].rgb(), RGB::from([0.5, 0.5, 0.5]));
+        assert_approx_eq!(swatches[3].rgb(), RGB::WHITE, 0.001);
+        assert_approx_eq!(
+            swatches[4].rgb(),
+            Lab::from([50.0, 20.0, -30.0]).rgb_with_white(Illuminant::D50),
+            0.000_001
+        );
+    }
+
+    #[test]
+    fn ase_errors() {
+        let data = file(&[entry("Odd", b"XYZ ", &[0.1, 0.2, 0.3])]);
+        match read_swatches::<f64, _>(data.as_slice()) {
+            Err(PaletteError::UnsupportedEntry(text)) => assert!(text.contains("XYZ")),
+            result => panic!("unexpected: {:?}", result),
+        }
+        let data = file(&[entry("Short", b"RGB ", &[0.1])]);
+        assert!(matches!(
+            read_swatches::<f64, _>(data.as_slice()),
+            Err(PaletteError::MalformedData(_))
+        ));
+        for bad in [
+            &b"ASEX\x00\x01\x00\x00\x00\x00\x00\x00"[..],
+            b"AS",
+            b"ASEF\x00\x02\x00\x00\x00\x00\x00\x00",
+        ]
+        .iter()
+        {
+            assert!(matches!(
+                read_swatches::<f64, _>(*bad),
+                Err(PaletteError::MalformedData(_))
+            ));
+        }
+        let long_name = "x".repeat(65_535);
+        let swatches = vec![Swatch::new(RGB::<f64>::RED, &long_name)];
+        assert!(matches!(
+            write_swatches(&swatches, &mut vec![]),
+            Err(PaletteError::TooLarge(_))
+        ));
+        let swatches = vec![Swatch::new(RGB::<f64>::RED, &long_name[1..])];
+        let mut data = vec![];
+        write_swatches(&swatches, &mut data).unwrap();
+        let read = read_swatches::<f64, _>(data.as_slice()).unwrap();
+        assert_eq!(read[0].name(), &long_name[1..]);
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{cie::XYZ, f, linear_rgb::LinearRGB, ColourComponent, Illuminant, RGB};
+
+/// The lowest reflectance used when estimating a pigment from an RGB colour (to avoid
+/// infinite absorption for colours with a zero component).
+pub const MIN_REFLECTANCE: f64 = 0.001;
+
+/// The Kubelka-Munk K/S ratio for a layer of (opaque) paint with the given reflectance.
+pub fn k_over_s<F: ColourComponent>(reflectance: F) -> F {
+    let reflectance = reflectance.max(f(MIN_REFLECTANCE)).min(F::ONE);
+    (F::ONE - reflectance).powi(2) / (F::TWO * reflectance)
+}
+
+/// The reflectance of an opaque layer of paint with the given Kubelka-Munk K/S ratio.
+pub fn reflectance<F: ColourComponent>(k_over_s: F) -> F {
+    debug_assert!(k_over_s >= F::ZERO, "K/S: {:?}", k_over_s);
+    (F::ONE + k_over_s - (k_over_s * k_over_s + F::TWO * k_over_s).sqrt())
+        .max(F::ZERO)
+        .min(F::ONE)
+}
+
+// Multi-lobe Gaussian fit of the CIE 1931 colour matching functions (Wyman, Sloan and
+// Shirley 2013)
+fn piecewise_gaussian(wavelength: f64, mean: f64, sigma_low: f64, sigma_high: f64) -> f64 {
+    let sigma = if wavelength < mean {
+        sigma_low
+    } else {
+        sigma_high
+    };
+    (-0.5 * ((wavelength - mean) / sigma).powi(2)).exp()
+}
+
+fn colour_matching_functions(wavelength: f64) -> [f64; 3] {
+    let g =
+        |mean, sigma_low, sigma_high| piecewise_gaussian(wavelength, mean, sigma_low, sigma_high);
+    [
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    ]
+}
+
+/// The sRGB colour of a surface with the given reflectance spectrum (viewed under an
+/// equal energy illuminant and adapted to D65).  A perfect reflector is white.
+pub fn rgb_for_reflectance_spectrum<F: ColourComponent>(
+    wavelengths: &[F],
+    reflectances: &[F],
+) -> RGB<F> {
+    debug_assert_eq!(wavelengths.len(), reflectances.len());
+    let mut xyz = [0.0_f64; 3];
+    let mut normalisers = [0.0_f64; 3];
+    for i in 1..wavelengths.len() {
+        let (wavelength_0, wavelength_1) = (
+            wavelengths[i - 1].to_f64().unwrap(),
+            wavelengths[i].to_f64().unwrap(),
+        );
+        debug_assert!(wavelength_1 > wavelength_0, "wavelengths must increase");
+        let half_width = 0.5 * (wavelength_1 - wavelength_0);
+        let (cmf_0, cmf_1) = (
+            colour_matching_functions(wavelength_0),
+            colour_matching_functions(wavelength_1),
+        );
+        let (reflectance_0, reflectance_1) = (
+            reflectances[i - 1].to_f64().unwrap(),
+            reflectances[i].to_f64().unwrap(),
+        );
+        for j in 0..3 {
+            xyz[j] += half_width * (cmf_0[j] * reflectance_0 + cmf_1[j] * reflectance_1);
+            normalisers[j] += half_width * (cmf_0[j] + cmf_1[j]);
+        }
+    }
+    if normalisers[1] <= 0.0 {
+        return RGB::from([F::ZERO; 3]);
+    }
+    let xyz: XYZ<F> = [
+        f(xyz[0] / normalisers[0]),
+        f(xyz[1] / normalisers[1]),
+        f(xyz[2] / normalisers[2]),
+    ]
+    .into();
+    xyz.rgb_with_white(Illuminant::E)
+}
+
+/// A paint modelled by its Kubelka-Munk absorption (K) and scattering (S) coefficients.
+/// The coefficients are either per linear RGB channel (when estimated from a colour) or
+/// per wavelength (when supplied spectrally).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Pigment<F: ColourComponent> {
+    absorption: Vec<F>,
+    scattering: Vec<F>,
+    wavelengths: Option<Vec<F>>,
+}
+
+impl<F: ColourComponent> Pigment<F> {
+    /// Estimate the coefficients of an opaque paint whose masstone is `rgb` (assuming
+    /// unit scattering in each channel).
+    pub fn from_rgb(rgb: &RGB<F>) -> Self {
+        let linear_rgb = LinearRGB::from(rgb);
+        Self {
+            absorption: linear_rgb.iter().map(|r| k_over_s(*r)).collect(),
+            scattering: vec![F::ONE; 3],
+            wavelengths: None,
+        }
+    }
+
+    /// A paint with the given coefficients sampled at the given (increasing) wavelengths
+    /// in nanometres.  Returns `None` if the lengths of the arguments differ, there are
+    /// fewer than two samples, the wavelengths are not finite and strictly increasing
+    /// or any coefficient is negative.
+    pub fn from_spectral(wavelengths: &[F], absorption: &[F], scattering: &[F]) -> Option<Self> {
+        if wavelengths.len() < 2
+            || absorption.len() != wavelengths.len()
+            || scattering.len() != wavelengths.len()
+            || wavelengths.iter().any(|w| !w.is_finite())
+            || wavelengths.windows(2).any(|pair| pair[0] >= pair[1])
+            || absorption
+                .iter()
+                .chain(scattering.iter())
+                .any(|c| *c < F::ZERO)
+        {
+            return None;
+        }
+        Some(Self {
+            absorption: absorption.to_vec(),
+            scattering: scattering.to_vec(),
+            wavelengths: Some(wavelengths.to_vec()),
+        })
+    }
+
+    pub fn absorption(&self) -> &[F] {
+        &self.absorption
+    }
+
+    pub fn scattering(&self) -> &[F] {
+        &self.scattering
+    }
+
+    /// The wavelengths at which the coefficients are sampled (`None` for linear RGB).
+    pub fn wavelengths(&self) -> Option<&[F]> {
+        self.wavelengths.as_deref()
+    }
+
+    fn is_compatible_with(&self, other: &Self) -> bool {
+        self.wavelengths == other.wavelengths
+    }
+
+    fn rgb_for_coefficients(&self, absorption: &[F], scattering: &[F]) -> RGB<F> {
+        let reflectances: Vec<F> = absorption
+            .iter()
+            .zip(scattering.iter())
+            .map(|(k, s)| {
+                if *s > F::ZERO {
+                    reflectance(*k / *s)
+                } else if *k > F::ZERO {
+                    F::ZERO
+                } else {
+                    F::ONE
+                }
+            })
+            .collect();
+        match self.wavelengths {
+            Some(ref wavelengths) => rgb_for_reflectance_spectrum(wavelengths, &reflectances),
+            None => LinearRGB::from([reflectances[0], reflectances[1], reflectances[2]]).into(),
+        }
+    }
+
+    /// The colour of an opaque layer of this paint.
+    pub fn rgb(&self) -> RGB<F> {
+        self.rgb_for_coefficients(&self.absorption, &self.scattering)
+    }
+}
+
+/// The predicted colour of a mixture of paints with the given weights (which need not
+/// sum to one).  Returns `None` if there are no paints, the total weight is not
+/// positive or the paints are not all sampled the same way.
+pub fn mixture_rgb<F: ColourComponent>(components: &[(&Pigment<F>, F)]) -> Option<RGB<F>> {
+    let (first, _) = components.first()?;
+    if components
+        .iter()
+        .any(|(pigment, weight)| !pigment.is_compatible_with(first) || *weight < F::ZERO)
+    {
+        return None;
+    }
+    let total_weight = components.iter().fold(F::ZERO, |acc, (_, w)| acc + *w);
+    if total_weight <= F::ZERO {
+        return None;
+    }
+    let n = first.absorption.len();
+    let mut absorption = vec![F::ZERO; n];
+    let mut scattering = vec![F::ZERO; n];
+    for (pigment, weight) in components.iter() {
+        let concentration = *weight / total_weight;
+        for i in 0..n {
+            absorption[i] += pigment.absorption[i] * concentration;
+            scattering[i] += pigment.scattering[i] * concentration;
+        }
+    }
+    Some(first.rgb_for_coefficients(&absorption, &scattering))
+}
+
+#[cfg(test)]
+mod pigment_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn k_over_s_round_trip() {
+        for r in [0.01, 0.1, 0.25, 0.5, 0.75, 1.0].iter() {
+            assert_approx_eq!(reflectance(k_over_s(*r)), *r, 0.000_000_001);
+        }
+        assert_eq!(k_over_s(1.0_f64), 0.0);
+    }
+
+    #[test]
+    fn rgb_estimates() {
+        for rgb in RGB::<f64>::PRIMARIES
+            .iter()
+            .chain(RGB::SECONDARIES.iter())
+            .chain(RGB::GREYS.iter())
+        {
+            let pigment = Pigment::from_rgb(rgb);
+            assert!(pigment.wavelengths().is_none());
+            // zero components are limited by MIN_REFLECTANCE
+            assert_approx_eq!(pigment.rgb(), *rgb, 0.015);
+            let mixture = mixture_rgb(&[(&pigment, 1.0), (&pigment, 3.0)]).unwrap();
+            assert_approx_eq!(mixture, pigment.rgb(), 0.000_000_001);
+        }
+    }
+
+    #[test]
+    fn blue_and_yellow_make_green() {
+        let blue = Pigment::from_rgb(&RGB::<f64>::from([0.1, 0.3, 0.7]));
+        let yellow = Pigment::from_rgb(&RGB::<f64>::from([0.95, 0.85, 0.1]));
+        let mixture = mixture_rgb(&[(&blue, 1.0), (&yellow, 1.0)]).unwrap();
+        let hue_angle = mixture.hue_angle().unwrap().degrees();
+        assert!(hue_angle > 90.0 && hue_angle < 180.0, "{:?}", hue_angle);
+        // whereas additive mixing is much greyer
+        let additive = RGB::<f64>::from([0.1, 0.3, 0.7]) * 0.5 + RGB::from([0.95, 0.85, 0.1]) * 0.5;
+        assert!(additive.chroma() < mixture.chroma());
+    }
+
+    #[test]
+    fn spectral_pigments() {
+        let wavelengths: Vec<f64> = (0..=35).map(|i| 380.0 + 10.0 * i as f64).collect();
+        let n = wavelengths.len();
+        let white = Pigment::from_spectral(&wavelengths, &vec![0.0; n], &vec![1.0; n]).unwrap();
+        assert_approx_eq!(white.rgb(), RGB::WHITE, 0.000_001);
+        // absorbs everything but long wavelengths
+        let absorption: Vec<f64> = wavelengths
+            .iter()
+            .map(|w| if *w < 580.0 { 20.0 } else { 0.0 })
+            .collect();
+        let red = Pigment::from_spectral(&wavelengths, &absorption, &vec![1.0; n]).unwrap();
+        let hue_angle = red.rgb().hue_angle().unwrap().degrees();
+        assert!(hue_angle > -30.0 && hue_angle < 60.0, "{:?}", hue_angle);
+        let pink = mixture_rgb(&[(&red, 1.0), (&white, 3.0)]).unwrap();
+        assert!(pink.value() > red.rgb().value());
+        assert!(pink.value() < 1.0);
+        // incompatible or missing data
+        let estimated = Pigment::from_rgb(&RGB::<f64>::RED);
+        assert!(mixture_rgb(&[(&red, 1.0), (&estimated, 1.0)]).is_none());
+        assert!(mixture_rgb::<f64>(&[]).is_none());
+        assert!(mixture_rgb(&[(&red, 0.0)]).is_none());
+        assert!(Pigment::from_spectral(&wavelengths, &[0.0], &vec![1.0; n]).is_none());
+        let unordered = [400.0, 500.0, 450.0];
+        assert!(Pigment::from_spectral(&unordered, &[0.0; 3], &[1.0; 3]).is_none());
+        let repeated = [400.0, 500.0, 500.0];
+        assert!(Pigment::from_spectral(&repeated, &[0.0; 3], &[1.0; 3]).is_none());
+        let not_finite = [400.0, 500.0, f64::NAN];
+        assert!(Pigment::from_spectral(&not_finite, &[0.0; 3], &[1.0; 3]).is_none());
+    }
+}
@@ -0,0 +1,398 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{image::ImageIfce, ColourComponent, RGB};
+
+/// The algorithms available for extracting a palette from an image.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Quantiser {
+    /// Lloyd's algorithm with k-means++ initialisation (the seed makes it repeatable)
+    KMeans { seed: u64 },
+    /// Recursively split the box with the widest range at the median of that range
+    MedianCut,
+    /// Merge the least populous leaves of an octree until few enough remain
+    Octree,
+}
+
+impl Default for Quantiser {
+    fn default() -> Self {
+        Quantiser::MedianCut
+    }
+}
+
+/// Up to `k` colours representing the pixels of `image` with the number of pixels each
+/// represents.  The colours are in descending order of pixel count.
+pub fn extract_palette<'a, F, I>(image: &I, k: usize, quantiser: Quantiser) -> Vec<(RGB<F>, usize)>
+where
+    F: ColourComponent + 'static,
+    I: ImageIfce<'a, RGB<F>>,
+{
+    palette_for_pixels(image.pixels(), k, quantiser)
+}
+
+/// As for `extract_palette()` but for a slice of pixels.
+pub fn palette_for_pixels<F: ColourComponent>(
+    pixels: &[RGB<F>],
+    k: usize,
+    quantiser: Quantiser,
+) -> Vec<(RGB<F>, usize)> {
+    if k == 0 || pixels.is_empty() {
+        return vec![];
+    }
+    let mut palette = match quantiser {
+        Quantiser::KMeans { seed } => k_means(pixels, k, seed),
+        Quantiser::MedianCut => median_cut(pixels, k),
+        Quantiser::Octree => octree(pixels, k),
+    };
+    palette.sort_by_key(|entry| std::cmp::Reverse(entry.1));
+    palette
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Accumulator<F: ColourComponent> {
+    sum: [F; 3],
+    count: usize,
+}
+
+impl<F: ColourComponent> Accumulator<F> {
+    fn add(&mut self, rgb: &RGB<F>) {
+        for (sum, component) in self.sum.iter_mut().zip(rgb.0.iter()) {
+            *sum += *component;
+        }
+        self.count += 1;
+    }
+
+    fn mean(&self) -> RGB<F> {
+        let count = F::from(self.count).unwrap();
+        let mut array = [F::ZERO; 3];
+        for (component, sum) in array.iter_mut().zip(self.sum.iter()) {
+            *component = (*sum / count).min(F::ONE);
+        }
+        RGB::from(array)
+    }
+}
+
+fn mean_and_count<F: ColourComponent>(pixels: &[RGB<F>]) -> (RGB<F>, usize) {
+    let mut accumulator = Accumulator::default();
+    for pixel in pixels.iter() {
+        accumulator.add(pixel);
+    }
+    (accumulator.mean(), accumulator.count)
+}
+
+fn distance_squared<F: ColourComponent>(rgb_1: &RGB<F>, rgb_2: &RGB<F>) -> F {
+    rgb_1
+        .0
+        .iter()
+        .zip(rgb_2.0.iter())
+        .fold(F::ZERO, |acc, (a, b)| acc + (*a - *b) * (*a - *b))
+}
+
+// SplitMix64: good enough (and repeatable) for choosing initial centres
+struct Random(u64);
+
+impl Random {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // In the range 0.0 to 1.0 (exclusive)
+    fn next_proportion<F: ColourComponent>(&mut self) -> F {
+        F::from(self.next_u64() >> 11).unwrap() / F::from(1_u64 << 53).unwrap()
+    }
+}
+
+const MAX_K_MEANS_ITERATIONS: usize = 64;
+
+fn k_means<F: ColourComponent>(pixels: &[RGB<F>], k: usize, seed: u64) -> Vec<(RGB<F>, usize)> {
+    let mut random = Random(seed);
+    let mut centres = vec![pixels[(random.next_u64() % pixels.len() as u64) as usize]];
+    let mut distances: Vec<F> = pixels
+        .iter()
+        .map(|p| distance_squared(p, &centres[0]))
+        .collect();
+    while centres.len() < k {
+        let total = distances.iter().copied().fold(F::ZERO, |acc, d| acc + d);
+        if total <= F::ZERO {
+            // fewer distinct colours than k
+            break;
+        }
+        let mut threshold = random.next_proportion::<F>() * total;
+        let mut chosen = pixels.len() - 1;
+        for (i, distance) in distances.iter().enumerate() {
+            if threshold < *distance {
+                chosen = i;
+                break;
+            }
+            threshold -= *distance;
+        }
+        let centre = pixels[chosen];
+        for (distance, pixel) in distances.iter_mut().zip(pixels.iter()) {
+            *distance = distance.min(distance_squared(pixel, &centre));
+        }
+        centres.push(centre);
+    }
+    let nearest = |pixel: &RGB<F>, centres: &[RGB<F>]| {
+        let mut best = (0, distance_squared(pixel, &centres[0]));
+        for (i, centre) in centres.iter().enumerate().skip(1) {
+            let distance = distance_squared(pixel, centre);
+            if distance < best.1 {
+                best = (i, distance);
+            }
+        }
+        best.0
+    };
+    let mut assignments: Vec<usize> = pixels.iter().map(|p| nearest(p, &centres)).collect();
+    let mut accumulators = vec![Accumulator::<F>::default(); centres.len()];
+    for _ in 0..MAX_K_MEANS_ITERATIONS {
+        accumulators = vec![Accumulator::default(); centres.len()];
+        for (pixel, assignment) in pixels.iter().zip(assignments.iter()) {
+            accumulators[*assignment].add(pixel);
+        }
+        for (centre, accumulator) in centres.iter_mut().zip(accumulators.iter()) {
+            if accumulator.count > 0 {
+                *centre = accumulator.mean();
+            }
+        }
+        let new_assignments: Vec<usize> = pixels.iter().map(|p| nearest(p, &centres)).collect();
+        if new_assignments == assignments {
+            break;
+        }
+        assignments = new_assignments;
+    }
+    accumulators
+        .iter()
+        .filter(|a| a.count > 0)
+        .map(|a| (a.mean(), a.count))
+        .collect()
+}
+
+fn widest_channel<F: ColourComponent>(pixels: &[RGB<F>]) -> (usize, F) {
+    let mut min = [F::ONE; 3];
+    let mut max = [F::ZERO; 3];
+    for pixel in pixels.iter() {
+        for i in 0..3 {
+            min[i] = min[i].min(pixel.0[i]);
+            max[i] = max[i].max(pixel.0[i]);
+        }
+    }
+    let mut widest = (0, max[0] - min[0]);
+    for i in 1..3 {
+        if max[i] - min[i] > widest.1 {
+            widest = (i, max[i] - min[i]);
+        }
+    }
+    widest
+}
+
+fn median_cut<F: ColourComponent>(pixels: &[RGB<F>], k: usize) -> Vec<(RGB<F>, usize)> {
+    let mut boxes = vec![pixels.to_vec()];
+    while boxes.len() < k {
+        let mut candidate: Option<(usize, usize, F)> = None;
+        for (i, pixels) in boxes.iter().enumerate() {
+            if pixels.len() < 2 {
+                continue;
+            }
+            let (channel, range) = widest_channel(pixels);
+            match candidate {
+                Some((_, _, widest_range)) if widest_range >= range => (),
+                _ if range > F::ZERO => candidate = Some((i, channel, range)),
+                _ => (),
+            }
+        }
+        if let Some((i, channel, _)) = candidate {
+            let mut pixels = boxes.swap_remove(i);
+            pixels.sort_by(|a, b| a.0[channel].partial_cmp(&b.0[channel]).unwrap());
+            // never split between equal values so that the halves are disjoint
+            let median = pixels[pixels.len() / 2].0[channel];
+            let split = match pixels.iter().position(|p| p.0[channel] >= median) {
+                Some(0) => pixels.iter().position(|p| p.0[channel] > median).unwrap(),
+                Some(index) => index,
+                None => unreachable!("median is a member"),
+            };
+            let upper = pixels.split_off(split);
+            boxes.push(pixels);
+            boxes.push(upper);
+        } else {
+            break;
+        }
+    }
+    boxes.iter().map(|pixels| mean_and_count(pixels)).collect()
+}
+
+const OCTREE_DEPTH: usize = 8;
+
+#[derive(Debug, Clone, Default)]
+struct OctreeNode<F: ColourComponent> {
+    children: [Option<usize>; 8],
+    accumulator: Accumulator<F>,
+    level: usize,
+}
+
+impl<F: ColourComponent> OctreeNode<F> {
+    fn is_leaf(&self) -> bool {
+        self.children.iter().all(|c| c.is_none())
+    }
+}
+
+fn octree<F: ColourComponent>(pixels: &[RGB<F>], k: usize) -> Vec<(RGB<F>, usize)> {
+    let scale = F::from(255.0).unwrap();
+    let mut nodes = vec![OctreeNode::<F>::default()];
+    for pixel in pixels.iter() {
+        let bytes: Vec<u8> = pixel
+            .0
+            .iter()
+            .map(|c| (*c * scale).round().to_u8().unwrap())
+            .collect();
+        let mut index = 0;
+        nodes[index].accumulator.add(pixel);
+        for level in 0..OCTREE_DEPTH {
+            let shift = 7 - level;
+            let octant = (((bytes[0] >> shift) & 1) << 2
+                | ((bytes[1] >> shift) & 1) << 1
+                | ((bytes[2] >> shift) & 1)) as usize;
+            index = match nodes[index].children[octant] {
+                Some(child) => child,
+                None => {
+                    nodes.push(OctreeNode {
+                        level: level + 1,
+                        ..OctreeNode::default()
+                    });
+                    let child = nodes.len() - 1;
+                    nodes[index].children[octant] = Some(child);
+                    child
+                }
+            };
+            nodes[index].accumulator.add(pixel);
+        }
+    }
+    let mut n_leaves = nodes.iter().filter(|n| n.is_leaf()).count();
+    // Every node deeper than the one being reduced is already a leaf so merging a node
+    // just means forgetting its children (its accumulator covers them).
+    let mut level = OCTREE_DEPTH;
+    while n_leaves > k && level > 0 {
+        level -= 1;
+        let mut reducible: Vec<usize> = (0..nodes.len())
+            .filter(|i| nodes[*i].level == level && !nodes[*i].is_leaf())
+            .collect();
+        reducible.sort_by_key(|i| nodes[*i].accumulator.count);
+        for i in reducible {
+            if n_leaves <= k {
+                break;
+            }
+            let n_children = nodes[i].children.iter().filter(|c| c.is_some()).count();
+            nodes[i].children = [None; 8];
+            n_leaves -= n_children - 1;
+        }
+    }
+    // Collect the leaves reachable from the root
+    let mut palette = vec![];
+    let mut stack = vec![0];
+    while let Some(i) = stack.pop() {
+        if nodes[i].is_leaf() {
+            let accumulator = &nodes[i].accumulator;
+            palette.push((accumulator.mean(), accumulator.count));
+        } else {
+            stack.extend(nodes[i].children.iter().filter_map(|c| *c));
+        }
+    }
+    palette
+}
+
+#[cfg(test)]
+mod quantise_tests {
+    use super::*;
+    use crate::{image::OpaqueImage, HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    const QUANTISERS: [Quantiser; 3] = [
+        Quantiser::KMeans { seed: 42 },
+        Quantiser::MedianCut,
+        Quantiser::Octree,
+    ];
+
+    fn test_pixels(noise: f64) -> Vec<RGB<f64>> {
+        let mut pixels = vec![];
+        for (rgb, count) in [(RGB::RED, 60), (RGB::BLUE, 30), (RGB::WHITE, 10)].iter() {
+            for i in 0..*count {
+                pixels.push(*rgb * (1.0 - (i % 5) as f64 * noise));
+            }
+        }
+        pixels
+    }
+
+    fn check_dominant_colours(palette: &[(RGB<f64>, usize)], quantiser: Quantiser) {
+        assert_eq!(palette.len(), 3, "{:?}", quantiser);
+        assert_eq!(
+            palette.iter().map(|(_, n)| *n).collect::<Vec<_>>(),
+            vec![60, 30, 10],
+            "{:?}",
+            quantiser
+        );
+        assert_approx_eq!(palette[0].0, RGB::RED, 0.01);
+        assert_approx_eq!(palette[1].0, RGB::BLUE, 0.01);
+        assert_approx_eq!(palette[2].0, RGB::WHITE, 0.01);
+    }
+
+    #[test]
+    fn dominant_colours() {
+        let image = OpaqueImage::from((test_pixels(0.0), 10));
+        for quantiser in QUANTISERS.iter() {
+            let palette = extract_palette(&image, 3, *quantiser);
+            check_dominant_colours(&palette, *quantiser);
+        }
+        // median cut splits at medians rather than looking for clusters
+        let image = OpaqueImage::from((test_pixels(0.002), 10));
+        for quantiser in [Quantiser::KMeans { seed: 42 }, Quantiser::Octree].iter() {
+            let palette = extract_palette(&image, 3, *quantiser);
+            check_dominant_colours(&palette, *quantiser);
+        }
+    }
+
+    #[test]
+    fn weights_cover_all_pixels() {
+        let pixels: Vec<RGB<f64>> = (0..256)
+            .map(|i| {
+                let x = i as f64 / 255.0;
+                RGB::from([x, (x * 7.0) % 1.0, 1.0 - x])
+            })
+            .collect();
+        for quantiser in QUANTISERS.iter() {
+            for k in [1, 2, 5, 16].iter() {
+                let palette = palette_for_pixels(&pixels, *k, *quantiser);
+                assert!(
+                    !palette.is_empty() && palette.len() <= *k,
+                    "{:?}",
+                    quantiser
+                );
+                let total: usize = palette.iter().map(|(_, n)| n).sum();
+                assert_eq!(total, pixels.len(), "{:?} {}", quantiser, k);
+                assert!(palette.windows(2).all(|w| w[0].1 >= w[1].1));
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_cases() {
+        let pixels = vec![RGB::<f64>::YELLOW; 20];
+        for quantiser in QUANTISERS.iter() {
+            assert!(palette_for_pixels::<f64>(&[], 4, *quantiser).is_empty());
+            assert!(palette_for_pixels(&pixels, 0, *quantiser).is_empty());
+            let palette = palette_for_pixels(&pixels, 4, *quantiser);
+            assert_eq!(palette, vec![(RGB::YELLOW, 20)], "{:?}", quantiser);
+        }
+    }
+
+    #[test]
+    fn k_means_is_repeatable() {
+        let pixels = test_pixels(0.002);
+        let quantiser = Quantiser::KMeans { seed: 7 };
+        assert_eq!(
+            palette_for_pixels(&pixels, 4, quantiser),
+            palette_for_pixels(&pixels, 4, quantiser)
+        );
+    }
+}
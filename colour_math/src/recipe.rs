@@ -0,0 +1,270 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use std::cmp::Ordering;
+
+use crate::{
+    difference::{difference, DifferenceMetric},
+    pigment::{mixture_rgb, Pigment},
+    ColourComponent, RGB,
+};
+
+/// A mixture of paints from a palette predicted to match a target colour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe<F: ColourComponent> {
+    components: Vec<(usize, F)>,
+    rgb: RGB<F>,
+    error: F,
+}
+
+impl<F: ColourComponent> Recipe<F> {
+    /// The palette indices of the paints in the mixture and their proportions (which sum
+    /// to one).
+    pub fn components(&self) -> &[(usize, F)] {
+        &self.components
+    }
+
+    /// The predicted colour of the mixture.
+    pub fn rgb(&self) -> RGB<F> {
+        self.rgb
+    }
+
+    /// The difference between the predicted colour and the target.
+    pub fn error(&self) -> F {
+        self.error
+    }
+}
+
+/// Finds the mixture of (up to `max_components`) paints from a palette that minimises
+/// the difference (measured by `metric`) from a target colour.  Rather than trying every
+/// combination of paints the search is pruned to the `beam_width` best recipes with each
+/// number of components, which are extended by one paint at a time.
+#[derive(Debug, Clone)]
+pub struct RecipeSolver<'a, F: ColourComponent> {
+    palette: &'a [Pigment<F>],
+    max_components: usize,
+    beam_width: usize,
+    metric: DifferenceMetric,
+    tolerance: F,
+}
+
+impl<'a, F: ColourComponent> RecipeSolver<'a, F> {
+    pub fn new(palette: &'a [Pigment<F>]) -> Self {
+        Self {
+            palette,
+            max_components: 3,
+            beam_width: 4,
+            metric: DifferenceMetric::default(),
+            tolerance: F::from(0.000_1).unwrap(),
+        }
+    }
+
+    /// The maximum number of paints in a recipe.  Solving optimises the proportions of
+    /// about `palette.len() * (1 + beam_width * (max_components - 1))` candidate
+    /// mixtures, so the cost grows linearly (rather than combinatorially) with it.
+    pub fn max_components(mut self, max_components: usize) -> Self {
+        self.max_components = max_components;
+        self
+    }
+
+    /// The number of the best recipes with each number of components that are extended
+    /// with further paints.  Wider beams are less likely to miss the best recipe but
+    /// take proportionally longer.
+    pub fn beam_width(mut self, beam_width: usize) -> Self {
+        debug_assert!(beam_width > 0);
+        self.beam_width = beam_width;
+        self
+    }
+
+    pub fn metric(mut self, metric: DifferenceMetric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// The smallest change in proportion tried when refining a recipe.
+    pub fn tolerance(mut self, tolerance: F) -> Self {
+        debug_assert!(tolerance > F::ZERO && tolerance < F::ONE);
+        self.tolerance = tolerance;
+        self
+    }
+
+    fn error_for(
+        &self,
+        buffer: &mut Vec<(&'a Pigment<F>, F)>,
+        indices: &[usize],
+        proportions: &[F],
+        target: &RGB<F>,
+    ) -> (RGB<F>, F) {
+        buffer.clear();
+        buffer.extend(
+            indices
+                .iter()
+                .zip(proportions.iter())
+                .map(|(i, p)| (&self.palette[*i], *p)),
+        );
+        let rgb = mixture_rgb(buffer).expect("palette paints are compatible");
+        (rgb, difference(&rgb, target, self.metric))
+    }
+
+    // Pattern search over the proportions: move mass between pairs of components while
+    // that reduces the error and then try smaller moves.
+    fn optimise(&self, indices: &[usize], target: &RGB<F>) -> Recipe<F> {
+        let n = indices.len();
+        let mut buffer = Vec::with_capacity(n);
+        let mut proportions = vec![F::ONE / F::from(n).unwrap(); n];
+        let mut trial = proportions.clone();
+        let (mut rgb, mut error) = self.error_for(&mut buffer, indices, &proportions, target);
+        let mut step = F::HALF / F::from(n).unwrap();
+        while n > 1 && step >= self.tolerance {
+            let mut improved = false;
+            for from in 0..n {
+                for to in 0..n {
+                    if from == to || proportions[from] <= F::ZERO {
+                        continue;
+                    }
+                    let delta = step.min(proportions[from]);
+                    trial.copy_from_slice(&proportions);
+                    trial[from] -= delta;
+                    trial[to] += delta;
+                    let (trial_rgb, trial_error) =
+                        self.error_for(&mut buffer, indices, &trial, target);
+                    if trial_error < error {
+                        proportions.copy_from_slice(&trial);
+                        rgb = trial_rgb;
+                        error = trial_error;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                step /= F::TWO;
+            }
+        }
+        let components = indices
+            .iter()
+            .copied()
+            .zip(proportions.iter().copied())
+            .filter(|(_, p)| *p > F::ZERO)
+            .collect();
+        Recipe {
+            components,
+            rgb,
+            error,
+        }
+    }
+
+    /// The best recipe for `target`.  Returns `None` if the palette is empty, the paints
+    /// in it are not all modelled the same way or `max_components` is zero.
+    pub fn solve(&self, target: &RGB<F>) -> Option<Recipe<F>> {
+        let first = self.palette.first()?;
+        if self.max_components == 0
+            || self
+                .palette
+                .iter()
+                .any(|pigment| pigment.wavelengths() != first.wavelengths())
+        {
+            return None;
+        }
+        let max_components = self.max_components.min(self.palette.len());
+        let mut beam: Vec<Vec<usize>> = vec![vec![]];
+        let mut best: Option<Recipe<F>> = None;
+        for _ in 0..max_components {
+            let mut candidates: Vec<(Vec<usize>, Recipe<F>)> = vec![];
+            for indices in beam.iter() {
+                for i in 0..self.palette.len() {
+                    if indices.contains(&i) {
+                        continue;
+                    }
+                    let mut extended = indices.clone();
+                    extended.push(i);
+                    extended.sort_unstable();
+                    if candidates.iter().any(|(c, _)| *c == extended) {
+                        continue;
+                    }
+                    let recipe = self.optimise(&extended, target);
+                    candidates.push((extended, recipe));
+                }
+            }
+            candidates.sort_by(|a, b| a.1.error.partial_cmp(&b.1.error).unwrap_or(Ordering::Equal));
+            candidates.truncate(self.beam_width.max(1));
+            match (&best, candidates.first()) {
+                (Some(best_recipe), Some((_, recipe))) if best_recipe.error <= recipe.error => (),
+                (_, Some((_, recipe))) => best = Some(recipe.clone()),
+                (_, None) => break,
+            }
+            beam = candidates.into_iter().map(|(indices, _)| indices).collect();
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod recipe_tests {
+    use super::*;
+    use crate::{HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn exact_matches() {
+        let palette: Vec<Pigment<f64>> = [RGB::RED, RGB::YELLOW, RGB::BLUE, RGB::WHITE]
+            .iter()
+            .map(Pigment::from_rgb)
+            .collect();
+        let solver = RecipeSolver::new(&palette);
+        let recipe = solver.solve(&palette[1].rgb()).unwrap();
+        assert_eq!(recipe.components(), &[(1, 1.0)]);
+        assert_approx_eq!(recipe.error(), 0.0, 0.000_000_001);
+        // a mixture that can be made from the palette
+        let target = mixture_rgb(&[(&palette[0], 0.3), (&palette[3], 0.7)]).unwrap();
+        let recipe = solver.solve(&target).unwrap();
+        assert!(recipe.error() < 0.1, "{:?}", recipe);
+        let total = recipe.components().iter().fold(0.0, |acc, (_, p)| acc + p);
+        assert_approx_eq!(total, 1.0, 0.000_000_001);
+    }
+
+    #[test]
+    fn component_limit() {
+        let palette: Vec<Pigment<f64>> = [RGB::RED, RGB::YELLOW, RGB::BLUE, RGB::WHITE]
+            .iter()
+            .map(Pigment::from_rgb)
+            .collect();
+        let target = RGB::<f64>::from([0.4, 0.5, 0.3]);
+        let mut previous_error = f64::MAX;
+        for max_components in 1..=3 {
+            let recipe = RecipeSolver::new(&palette)
+                .max_components(max_components)
+                .metric(DifferenceMetric::CIE76)
+                .solve(&target)
+                .unwrap();
+            assert!(recipe.components().len() <= max_components);
+            assert!(recipe.error() <= previous_error);
+            previous_error = recipe.error();
+        }
+        assert!(RecipeSolver::<f64>::new(&[]).solve(&target).is_none());
+        assert!(RecipeSolver::new(&palette)
+            .max_components(0)
+            .solve(&target)
+            .is_none());
+    }
+
+    #[test]
+    fn large_palette() {
+        let levels = [0.1, 0.5, 0.9];
+        let mut palette: Vec<Pigment<f64>> = vec![];
+        for red in levels.iter() {
+            for green in levels.iter() {
+                for blue in levels.iter() {
+                    palette.push(Pigment::from_rgb(&RGB::from([*red, *green, *blue])));
+                }
+            }
+        }
+        let target = mixture_rgb(&[(&palette[5], 0.3), (&palette[20], 0.7)]).unwrap();
+        let recipe = RecipeSolver::new(&palette).solve(&target).unwrap();
+        assert!(recipe.error() < 0.1, "{:?}", recipe);
+        let narrow = RecipeSolver::new(&palette)
+            .beam_width(1)
+            .solve(&target)
+            .unwrap();
+        assert!(narrow.components().len() <= 3);
+        assert!(recipe.error() <= narrow.error());
+    }
+}
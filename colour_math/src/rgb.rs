@@ -12,6 +12,8 @@ pub use crate::{
 };
 
 use crate::chroma::HueData;
+use crate::contrast;
+use crate::error::{self, ColourMathError};
 use crate::HueIfce;
 use normalised_angles::Degrees;
 use num_traits_plus::float_plus::*;
@@ -109,6 +111,18 @@ impl<F: ColourComponent> RGB<F> {
         self.0.iter().all(|x| (*x).is_proportion())
     }
 
+    /// Returns an error unless all of the components are in the range 0.0 to 1.0.
+    pub fn try_new(array: [F; 3]) -> Result<Self, ColourMathError> {
+        error::check_proportions("RGB components", &array)?;
+        Ok(Self(array))
+    }
+
+    /// Returns an error unless `slice` holds exactly three valid components.
+    pub fn try_from_slice(slice: &[F]) -> Result<Self, ColourMathError> {
+        error::check_length("RGB components", slice.len(), 3)?;
+        Self::try_new([slice[0], slice[1], slice[2]])
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &F> {
         self.0.iter()
     }
@@ -465,15 +479,7 @@ impl<F: ColourComponent> ColourInterface<F> for RGB<F> {
     }
 
     fn best_foreground_rgb(&self) -> RGB<F> {
-        if self[CCI::Red] * F::from(0.299).unwrap()
-            + self[CCI::Green] * F::from(0.587).unwrap()
-            + self[CCI::Blue] * F::from(0.114).unwrap()
-            > F::HALF
-        {
-            Self::BLACK
-        } else {
-            Self::WHITE
-        }
+        contrast::best_foreground_rgb_from(self, &[Self::BLACK, Self::WHITE]).expect("not empty")
     }
 }
 
@@ -572,4 +578,29 @@ mod rgb_tests {
             }
         }
     }
+
+    #[test]
+    fn fallible_construction() {
+        assert_eq!(RGB::try_new([0.0, 0.5, 1.0]).unwrap(), RGB([0.0, 0.5, 1.0]));
+        assert!(matches!(
+            RGB::try_new([0.0, 1.5, 1.0]),
+            Err(ColourMathError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            RGB::<f64>::try_new([f64::NAN, 0.5, 1.0]),
+            Err(ColourMathError::OutOfRange(_))
+        ));
+        assert_eq!(
+            RGB::try_from_slice(&[1.0, 1.0, 0.0]).unwrap(),
+            RGB::<f64>::YELLOW
+        );
+        assert!(matches!(
+            RGB::<f64>::try_from_slice(&[1.0, 1.0]),
+            Err(ColourMathError::BadLength(_))
+        ));
+        assert!(matches!(
+            RGB::try_from_slice(&[1.0, -0.1, 0.0]),
+            Err(ColourMathError::OutOfRange(_))
+        ));
+    }
 }
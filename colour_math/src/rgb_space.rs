@@ -0,0 +1,411 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! RGB colour spaces other than sRGB (which is what `RGB` implicitly uses) and
+//! conversion between them via XYZ.
+
+use num_traits_plus::float_plus::*;
+
+use crate::{
+    cie::{apply_matrix, SRGB_TO_XYZ, XYZ_TO_SRGB},
+    clamped, f,
+    illuminant::{matrix_product, ChromaticAdaptation, Illuminant},
+    linear_rgb::{linear_to_srgb, srgb_to_linear},
+    ColourComponent, RGB, XYZ,
+};
+
+fn matrix_inverse(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let cofactor = |i: usize, j: usize| {
+        let (r0, r1) = ((i + 1) % 3, (i + 2) % 3);
+        let (c0, c1) = ((j + 1) % 3, (j + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let determinant = (0..3).map(|j| m[0][j] * cofactor(0, j)).sum::<f64>();
+    let mut inverse = [[0.0; 3]; 3];
+    for (i, row) in inverse.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = cofactor(j, i) / determinant;
+        }
+    }
+    inverse
+}
+
+// BT.2020 transfer function constants (for 12 bit precision)
+const REC_2020_ALPHA: f64 = 1.099_296_826_809_44;
+const REC_2020_BETA: f64 = 0.018_053_968_510_807;
+
+/// RGB colour spaces defined by their primaries, white point and transfer function.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RGBSpace {
+    SRGB,
+    /// DCI-P3 primaries with the sRGB white point and transfer function
+    DisplayP3,
+    /// Adobe RGB (1998)
+    AdobeRGB,
+    /// ITU-R BT.2020 (UHDTV)
+    Rec2020,
+}
+
+impl Default for RGBSpace {
+    fn default() -> Self {
+        RGBSpace::SRGB
+    }
+}
+
+impl RGBSpace {
+    pub const ALL: [RGBSpace; 4] = [
+        RGBSpace::SRGB,
+        RGBSpace::DisplayP3,
+        RGBSpace::AdobeRGB,
+        RGBSpace::Rec2020,
+    ];
+
+    /// The xy chromaticity coordinates of the red, green and blue primaries.
+    pub fn primaries(&self) -> [(f64, f64); 3] {
+        match self {
+            RGBSpace::SRGB => [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+            RGBSpace::DisplayP3 => [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)],
+            RGBSpace::AdobeRGB => [(0.64, 0.33), (0.21, 0.71), (0.15, 0.06)],
+            RGBSpace::Rec2020 => [(0.708, 0.292), (0.170, 0.797), (0.131, 0.046)],
+        }
+    }
+
+    pub fn white(&self) -> Illuminant {
+        Illuminant::D65
+    }
+
+    /// The matrix that maps linear components to XYZ coordinates relative to this
+    /// space's white point.
+    pub fn to_xyz_matrix(&self) -> [[f64; 3]; 3] {
+        if *self == RGBSpace::SRGB {
+            return SRGB_TO_XYZ;
+        }
+        let mut primaries = [[0.0; 3]; 3];
+        for (j, (x, y)) in self.primaries().iter().enumerate() {
+            primaries[0][j] = x / y;
+            primaries[1][j] = 1.0;
+            primaries[2][j] = (1.0 - x - y) / y;
+        }
+        let scale = apply_matrix(
+            &matrix_inverse(&primaries),
+            self.white().white_point_array(),
+        );
+        for row in primaries.iter_mut() {
+            for (cell, scale) in row.iter_mut().zip(scale.iter()) {
+                *cell *= scale;
+            }
+        }
+        primaries
+    }
+
+    pub fn from_xyz_matrix(&self) -> [[f64; 3]; 3] {
+        if *self == RGBSpace::SRGB {
+            XYZ_TO_SRGB
+        } else {
+            matrix_inverse(&self.to_xyz_matrix())
+        }
+    }
+
+    /// The matrix that maps linear components in this space to linear components in
+    /// `other` (adapting between white points if necessary).
+    pub fn conversion_matrix(&self, other: RGBSpace) -> [[f64; 3]; 3] {
+        let adaptation = ChromaticAdaptation::default().matrix(self.white(), other.white());
+        matrix_product(
+            &other.from_xyz_matrix(),
+            &matrix_product(&adaptation, &self.to_xyz_matrix()),
+        )
+    }
+
+    /// Remove the transfer function from an encoded component (0.0 to 1.0).
+    pub fn decode<F: ColourComponent>(&self, encoded: F) -> F {
+        let encoded = clamped(encoded);
+        match self {
+            RGBSpace::SRGB | RGBSpace::DisplayP3 => srgb_to_linear(encoded),
+            RGBSpace::AdobeRGB => encoded.powf(f(563.0 / 256.0)),
+            RGBSpace::Rec2020 => {
+                if encoded < f(4.5 * REC_2020_BETA) {
+                    encoded / f(4.5)
+                } else {
+                    ((encoded + f(REC_2020_ALPHA - 1.0)) / f(REC_2020_ALPHA)).powf(f(1.0 / 0.45))
+                }
+            }
+        }
+    }
+
+    /// Apply the transfer function to a linear component (0.0 to 1.0).
+    pub fn encode<F: ColourComponent>(&self, linear: F) -> F {
+        let linear = clamped(linear);
+        match self {
+            RGBSpace::SRGB | RGBSpace::DisplayP3 => linear_to_srgb(linear),
+            RGBSpace::AdobeRGB => linear.powf(f(256.0 / 563.0)),
+            RGBSpace::Rec2020 => {
+                if linear < f(REC_2020_BETA) {
+                    linear * f(4.5)
+                } else {
+                    (f::<F>(REC_2020_ALPHA) * linear.powf(f(0.45)) - f(REC_2020_ALPHA - 1.0))
+                        .min(F::ONE)
+                }
+            }
+        }
+    }
+
+    /// Whether the colour with these XYZ coordinates (relative to D65) can be
+    /// represented in this space (allowing for rounding errors).
+    pub fn is_in_gamut<F: ColourComponent>(&self, xyz: &XYZ<F>) -> bool {
+        let tolerance = f::<F>(0.000_001);
+        self.linear_for_xyz(xyz)
+            .iter()
+            .all(|c| *c >= -tolerance && *c <= F::ONE + tolerance)
+    }
+
+    // Linear components without any clamping.
+    fn linear_for_xyz<F: ColourComponent>(&self, xyz: &XYZ<F>) -> [F; 3] {
+        let xyz = ChromaticAdaptation::default().adapt(xyz, Illuminant::D65, self.white());
+        apply_matrix(&self.from_xyz_matrix(), [xyz.x, xyz.y, xyz.z])
+    }
+}
+
+/// How to bring colours that are outside the gamut of the destination space into it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamutMapping {
+    /// Clamp each linear component into range (which may shift the hue).
+    Clip,
+    /// Reduce the chroma (keeping the luminance and hue) until the colour fits.
+    Compress,
+}
+
+impl Default for GamutMapping {
+    fn default() -> Self {
+        GamutMapping::Clip
+    }
+}
+
+impl GamutMapping {
+    /// Map linear components (of a space whose white has luminance 1) into range.
+    pub fn apply<F: ColourComponent>(&self, space: RGBSpace, linear: [F; 3]) -> [F; 3] {
+        match self {
+            GamutMapping::Clip => [clamped(linear[0]), clamped(linear[1]), clamped(linear[2])],
+            GamutMapping::Compress => {
+                let matrix = space.to_xyz_matrix();
+                let luminance =
+                    clamped((0..3).map(|j| f::<F>(matrix[1][j]) * linear[j]).sum::<F>());
+                // the largest proportion of the distance from the grey that fits
+                let mut proportion = F::ONE;
+                for component in linear.iter() {
+                    let delta = *component - luminance;
+                    if *component > F::ONE {
+                        proportion = proportion.min((F::ONE - luminance) / delta);
+                    } else if *component < F::ZERO {
+                        proportion = proportion.min(-luminance / delta);
+                    }
+                }
+                let mut result = [F::ZERO; 3];
+                for (r, component) in result.iter_mut().zip(linear.iter()) {
+                    *r = clamped(luminance + (*component - luminance) * proportion);
+                }
+                result
+            }
+        }
+    }
+}
+
+/// RGB components (0.0 to 1.0) encoded for a specific colour space.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct TaggedRGB<F: ColourComponent> {
+    pub(crate) space: RGBSpace,
+    pub(crate) components: [F; 3],
+}
+
+impl<F: ColourComponent> TaggedRGB<F> {
+    pub fn new(space: RGBSpace, components: [F; 3]) -> Self {
+        debug_assert!(components.iter().all(|c| c.is_proportion()));
+        Self { space, components }
+    }
+
+    pub fn space(&self) -> RGBSpace {
+        self.space
+    }
+
+    pub fn components(&self) -> [F; 3] {
+        self.components
+    }
+
+    /// The components with the transfer function removed.
+    pub fn linear(&self) -> [F; 3] {
+        let mut linear = self.components;
+        for component in linear.iter_mut() {
+            *component = self.space.decode(*component);
+        }
+        linear
+    }
+
+    /// The XYZ coordinates (relative to D65).
+    pub fn xyz(&self) -> XYZ<F> {
+        let xyz: XYZ<F> = apply_matrix(&self.space.to_xyz_matrix(), self.linear()).into();
+        ChromaticAdaptation::default().adapt(&xyz, self.space.white(), Illuminant::D65)
+    }
+
+    /// The colour with the given XYZ coordinates (relative to D65) in `space` with
+    /// out of gamut colours brought into range by `mapping`.
+    pub fn from_xyz(xyz: &XYZ<F>, space: RGBSpace, mapping: GamutMapping) -> Self {
+        let mut components = mapping.apply(space, space.linear_for_xyz(xyz));
+        for component in components.iter_mut() {
+            *component = space.encode(*component);
+        }
+        Self { space, components }
+    }
+
+    /// Whether this colour can be represented in `space`.
+    pub fn is_in_gamut(&self, space: RGBSpace) -> bool {
+        space.is_in_gamut(&self.xyz())
+    }
+
+    pub fn converted(&self, space: RGBSpace, mapping: GamutMapping) -> Self {
+        if space == self.space {
+            *self
+        } else {
+            Self::from_xyz(&self.xyz(), space, mapping)
+        }
+    }
+
+    /// The sRGB equivalent of this colour.
+    pub fn rgb_with_mapping(&self, mapping: GamutMapping) -> RGB<F> {
+        RGB::from(self.converted(RGBSpace::SRGB, mapping).components)
+    }
+}
+
+impl<F: ColourComponent> FloatApproxEq<F> for TaggedRGB<F> {
+    fn approx_eq(&self, other: &Self, max_diff: Option<F>) -> bool {
+        self.space == other.space
+            && (0..3).all(|i| self.components[i].approx_eq(&other.components[i], max_diff))
+    }
+}
+
+impl<F: ColourComponent> From<&RGB<F>> for TaggedRGB<F> {
+    fn from(rgb: &RGB<F>) -> Self {
+        Self::new(RGBSpace::SRGB, rgb.0)
+    }
+}
+
+impl<F: ColourComponent> From<RGB<F>> for TaggedRGB<F> {
+    fn from(rgb: RGB<F>) -> Self {
+        (&rgb).into()
+    }
+}
+
+/// Colours outside the sRGB gamut are clipped to it.
+impl<F: ColourComponent> From<&TaggedRGB<F>> for RGB<F> {
+    fn from(tagged_rgb: &TaggedRGB<F>) -> Self {
+        tagged_rgb.rgb_with_mapping(GamutMapping::Clip)
+    }
+}
+
+impl<F: ColourComponent> From<TaggedRGB<F>> for RGB<F> {
+    fn from(tagged_rgb: TaggedRGB<F>) -> Self {
+        (&tagged_rgb).into()
+    }
+}
+
+impl_colour_interface_via_rgb!(TaggedRGB);
+
+#[cfg(test)]
+mod rgb_space_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants};
+    use num_traits_plus::assert_approx_eq;
+
+    #[test]
+    fn matrices() {
+        // the sRGB constants should agree with those derived from its primaries
+        let mut primaries = [[0.0; 3]; 3];
+        for (j, (x, y)) in RGBSpace::SRGB.primaries().iter().enumerate() {
+            primaries[0][j] = x / y;
+            primaries[1][j] = 1.0;
+            primaries[2][j] = (1.0 - x - y) / y;
+        }
+        let scale = apply_matrix(
+            &matrix_inverse(&primaries),
+            RGBSpace::SRGB.white().white_point_array(),
+        );
+        for (row, expected_row) in primaries.iter_mut().zip(SRGB_TO_XYZ.iter()) {
+            for ((cell, scale), expected) in row.iter_mut().zip(scale.iter()).zip(expected_row) {
+                *cell *= scale;
+                assert_approx_eq!(*cell, *expected, 0.000_1);
+            }
+        }
+        // published values for the luminance of the primaries
+        let expected_y = [
+            (RGBSpace::DisplayP3, [0.228_975, 0.691_739, 0.079_287]),
+            (RGBSpace::AdobeRGB, [0.297_345, 0.627_357, 0.075_298]),
+            (RGBSpace::Rec2020, [0.262_700, 0.677_998, 0.059_302]),
+        ];
+        for (space, y) in expected_y.iter() {
+            let matrix = space.to_xyz_matrix();
+            for j in 0..3 {
+                assert_approx_eq!(matrix[1][j], y[j], 0.000_01);
+            }
+            let round_trip = matrix_product(&space.from_xyz_matrix(), &matrix);
+            assert_approx_eq!(round_trip[1][1], 1.0, 0.000_000_001);
+        }
+        let identity = RGBSpace::AdobeRGB.conversion_matrix(RGBSpace::AdobeRGB);
+        assert_approx_eq!(identity[0][0], 1.0, 0.000_000_001);
+        assert_approx_eq!(identity[0][1], 0.0, 0.000_000_001);
+    }
+
+    #[test]
+    fn transfer_functions() {
+        let values = [0.0, 0.001, 0.01, 0.05, 0.2, 0.5, 0.8, 1.0];
+        for space in RGBSpace::ALL.iter() {
+            for value in values.iter() {
+                assert_approx_eq!(space.encode(space.decode(*value)), *value, 0.000_000_001);
+            }
+            assert_eq!(space.decode(0.0_f64), 0.0);
+            assert_approx_eq!(space.decode(1.0_f64), 1.0, 0.000_000_001);
+        }
+        assert_approx_eq!(RGBSpace::AdobeRGB.decode(0.5_f64), 0.217_756, 0.000_001);
+    }
+
+    #[test]
+    fn conversions() {
+        for space in RGBSpace::ALL.iter() {
+            let white = TaggedRGB::new(*space, [1.0_f64, 1.0, 1.0]);
+            assert_approx_eq!(RGB::from(white), RGB::WHITE, 0.000_000_001);
+            let grey = TaggedRGB::from(RGB::<f64>::from([0.5, 0.5, 0.5]));
+            assert_approx_eq!(
+                grey.converted(*space, GamutMapping::Clip).components()[0],
+                grey.converted(*space, GamutMapping::Clip).components()[2],
+                0.000_000_001
+            );
+            for rgb in RGB::<f64>::PRIMARIES.iter() {
+                let tagged = TaggedRGB::from(rgb).converted(*space, GamutMapping::Clip);
+                assert!(tagged.is_in_gamut(RGBSpace::SRGB));
+                assert_approx_eq!(RGB::from(tagged), *rgb, 0.000_000_001);
+            }
+        }
+        // Adobe RGB pixels are more saturated than the same numbers read as sRGB
+        let adobe_green = TaggedRGB::new(RGBSpace::AdobeRGB, [0.0_f64, 1.0, 0.0]);
+        assert!(!adobe_green.is_in_gamut(RGBSpace::SRGB));
+        assert!(adobe_green.is_in_gamut(RGBSpace::Rec2020));
+        assert!(adobe_green.xyz().y() < RGB::<f64>::GREEN.xyz().y());
+    }
+
+    #[test]
+    fn gamut_mapping() {
+        let p3_red = TaggedRGB::new(RGBSpace::DisplayP3, [1.0_f64, 0.0, 0.0]);
+        assert!(!p3_red.is_in_gamut(RGBSpace::SRGB));
+        let clipped = p3_red.rgb_with_mapping(GamutMapping::Clip);
+        assert_approx_eq!(clipped, RGB::RED, 0.000_001);
+        let compressed = p3_red.rgb_with_mapping(GamutMapping::Compress);
+        assert!(TaggedRGB::from(compressed).is_in_gamut(RGBSpace::SRGB));
+        // compression keeps the luminance but loses some chroma
+        assert_approx_eq!(compressed.xyz().y(), p3_red.xyz().y(), 0.000_001);
+        assert!(compressed.chroma() < clipped.chroma());
+        // colours already in gamut are unchanged
+        let inside = TaggedRGB::new(RGBSpace::Rec2020, [0.4_f64, 0.5, 0.45]);
+        assert!(inside.is_in_gamut(RGBSpace::SRGB));
+        assert_approx_eq!(
+            inside.rgb_with_mapping(GamutMapping::Compress),
+            inside.rgb_with_mapping(GamutMapping::Clip),
+            0.000_000_001
+        );
+    }
+}
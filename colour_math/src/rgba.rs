@@ -8,7 +8,11 @@ use std::{
 
 pub use crate::{chroma, hcv::*, hue::*, ColourComponent, ColourInterface};
 
-use crate::{rgb::RGB, HueConstants, RGBConstants};
+use crate::{
+    error::{self, ColourMathError},
+    rgb::RGB,
+    HueConstants, RGBConstants,
+};
 
 use normalised_angles::Degrees;
 use num_traits_plus::float_plus::*;
@@ -32,6 +36,18 @@ impl<F: ColourComponent> RGBConstants for RGBA<F> {
 }
 
 impl<F: ColourComponent> RGBA<F> {
+    /// Returns an error unless all of the components are in the range 0.0 to 1.0.
+    pub fn try_new(array: [F; 4]) -> Result<Self, ColourMathError> {
+        error::check_proportions("RGBA components", &array)?;
+        Ok(Self(array))
+    }
+
+    /// Returns an error unless `slice` holds exactly four valid components.
+    pub fn try_from_slice(slice: &[F]) -> Result<Self, ColourMathError> {
+        error::check_length("RGBA components", slice.len(), 4)?;
+        Self::try_new([slice[0], slice[1], slice[2], slice[3]])
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &F> {
         self.0.iter()
     }
@@ -351,4 +367,24 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn fallible_construction() {
+        assert_eq!(
+            RGBA::try_new([0.0, 0.5, 1.0, 0.25]).unwrap(),
+            RGBA([0.0, 0.5, 1.0, 0.25])
+        );
+        assert!(matches!(
+            RGBA::try_new([0.0, 0.5, 1.0, 1.25]),
+            Err(ColourMathError::OutOfRange(_))
+        ));
+        assert!(matches!(
+            RGBA::<f64>::try_from_slice(&[0.0, 0.5, 1.0]),
+            Err(ColourMathError::BadLength(_))
+        ));
+        assert_eq!(
+            RGBA::try_from_slice(&[1.0, 0.0, 0.0, 1.0]).unwrap(),
+            RGBA::<f64>::RED
+        );
+    }
 }
@@ -0,0 +1,346 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+//! An opt-in human readable serde format for colours intended for hand edited files.
+//! Use it for a field with `#[serde(with = "colour_math::serde_readable")]` or wrap
+//! values (e.g. the members of a collection) in `Readable`.  Deserialisation accepts
+//! both the readable and the compact (default) representations.  8 bit colours are
+//! written as CSS hex (e.g. `"#rrggbb"`) and 16 bit ones as its 16 bit analogue (e.g.
+//! `"#rrrrggggbbbb"`).
+
+use std::str::FromStr;
+
+use serde::{
+    de::{DeserializeOwned, Error},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    css::{CSSColour, CSSFormat},
+    urgb::URGB,
+    urgba::URGBA,
+    ColourComponent, Degrees, HCV, RGB, RGBA,
+};
+
+pub trait ReadableSerde: Sized {
+    fn serialize_readable<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn deserialize_readable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+pub fn serialize<T: ReadableSerde, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.serialize_readable(serializer)
+}
+
+pub fn deserialize<'de, T: ReadableSerde, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    T::deserialize_readable(deserializer)
+}
+
+/// A wrapper that uses the readable format for the wrapped colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Readable<T>(pub T);
+
+impl<T: ReadableSerde> Serialize for Readable<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_readable(serializer)
+    }
+}
+
+impl<'de, T: ReadableSerde> Deserialize<'de> for Readable<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Readable(T::deserialize_readable(deserializer)?))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextOrCompact<C> {
+    Text(String),
+    Compact(C),
+}
+
+impl ReadableSerde for URGB<u8> {
+    /// Written as `"#rrggbb"`.  Any text accepted by `FromStr` can be read.
+    fn serialize_readable<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.css_string(CSSFormat::Hex))
+    }
+
+    fn deserialize_readable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match TextOrCompact::<Self>::deserialize(deserializer)? {
+            TextOrCompact::Text(text) => Self::from_str(&text).map_err(D::Error::custom),
+            TextOrCompact::Compact(urgb) => Ok(urgb),
+        }
+    }
+}
+
+impl ReadableSerde for URGBA<u8> {
+    /// Written as `"#rrggbbaa"` (or `"#rrggbb"` if opaque).  Any text accepted by
+    /// `FromStr` can be read.
+    fn serialize_readable<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.css_string(CSSFormat::Hex))
+    }
+
+    fn deserialize_readable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match TextOrCompact::<Self>::deserialize(deserializer)? {
+            TextOrCompact::Text(text) => Self::from_str(&text).map_err(D::Error::custom),
+            TextOrCompact::Compact(urgba) => Ok(urgba),
+        }
+    }
+}
+
+fn hex16_string(components: &[u16]) -> String {
+    components
+        .iter()
+        .fold("#".to_string(), |string, c| string + &format!("{:04x}", c))
+}
+
+// The components of "#" followed by four hex digits for each of them (if `text` is
+// of that form with a permitted number of components).
+fn parse_hex16(text: &str, counts: &[usize]) -> Option<Vec<u16>> {
+    let digits = text.trim().strip_prefix('#')?;
+    let count = digits.len() / 4;
+    if digits.len() % 4 != 0 || !counts.contains(&count) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(4)
+        .map(|i| {
+            let hex = digits.get(i..i + 4)?;
+            if hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                u16::from_str_radix(hex, 16).ok()
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+impl ReadableSerde for URGB<u16> {
+    /// Written as `"#rrrrggggbbbb"`.  Any text accepted by `FromStr` or any CSS
+    /// colour can be read.
+    fn serialize_readable<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex16_string(&[self[0], self[1], self[2]]))
+    }
+
+    fn deserialize_readable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match TextOrCompact::<Self>::deserialize(deserializer)? {
+            TextOrCompact::Text(text) => {
+                if let Some(c) = parse_hex16(&text, &[3]) {
+                    Ok(Self::from([c[0], c[1], c[2]]))
+                } else if let Ok(urgb) = Self::from_str(&text) {
+                    Ok(urgb)
+                } else {
+                    let rgb = RGB::<f64>::from_css_str(&text).map_err(D::Error::custom)?;
+                    Ok(Self::from(rgb))
+                }
+            }
+            TextOrCompact::Compact(urgb) => Ok(urgb),
+        }
+    }
+}
+
+impl ReadableSerde for URGBA<u16> {
+    /// Written as `"#rrrrggggbbbbaaaa"` (or `"#rrrrggggbbbb"` if opaque).  Any text
+    /// accepted by `FromStr` or any CSS colour can be read.
+    fn serialize_readable<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let string = if self[3] == u16::MAX {
+            hex16_string(&[self[0], self[1], self[2]])
+        } else {
+            hex16_string(&[self[0], self[1], self[2], self[3]])
+        };
+        serializer.serialize_str(&string)
+    }
+
+    fn deserialize_readable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match TextOrCompact::<Self>::deserialize(deserializer)? {
+            TextOrCompact::Text(text) => {
+                if let Some(c) = parse_hex16(&text, &[3, 4]) {
+                    let alpha = c.get(3).copied().unwrap_or(u16::MAX);
+                    Ok(Self::from([c[0], c[1], c[2], alpha]))
+                } else if let Ok(urgba) = Self::from_str(&text) {
+                    Ok(urgba)
+                } else {
+                    let rgba = RGBA::<f64>::from_css_str(&text).map_err(D::Error::custom)?;
+                    Ok(Self::from(rgba))
+                }
+            }
+            TextOrCompact::Compact(urgba) => Ok(urgba),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReadableHCV<F: ColourComponent> {
+    hue: Option<F>,
+    chroma: F,
+    value: F,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReadableOrCompactHCV<F: ColourComponent> {
+    Readable(ReadableHCV<F>),
+    Compact(HCV<F>),
+}
+
+impl<F: ColourComponent + Serialize + DeserializeOwned> ReadableSerde for HCV<F> {
+    /// Written as `{hue, chroma, value}` with the hue in degrees (`null` for greys).
+    fn serialize_readable<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ReadableHCV {
+            hue: self.hue_angle().map(|angle| angle.degrees()),
+            chroma: self.chroma(),
+            value: self.value(),
+        }
+        .serialize(serializer)
+    }
+
+    fn deserialize_readable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match ReadableOrCompactHCV::<F>::deserialize(deserializer)? {
+            ReadableOrCompactHCV::Readable(readable) => HCV::with_hue_chroma_value(
+                readable.hue.map(Degrees::from),
+                readable.chroma,
+                readable.value,
+            )
+            .ok_or_else(|| {
+                D::Error::custom(format!(
+                    "invalid HCV: hue={:?}, chroma={:?}, value={:?}",
+                    readable.hue, readable.chroma, readable.value
+                ))
+            }),
+            ReadableOrCompactHCV::Compact(hcv) => {
+                HCV::with_hue_chroma_value(hcv.hue_angle(), hcv.chroma(), hcv.value())
+                    .map(|_| hcv)
+                    .ok_or_else(|| D::Error::custom(format!("invalid HCV: {:?}", hcv)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod serde_readable_tests {
+    use super::*;
+    use crate::{HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+    use std::convert::TryFrom;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        #[serde(with = "crate::serde_readable")]
+        background: URGB<u8>,
+        #[serde(with = "crate::serde_readable")]
+        highlight: URGBA<u8>,
+        #[serde(with = "crate::serde_readable")]
+        target: HCV<f64>,
+        palette: Vec<Readable<URGB<u8>>>,
+    }
+
+    #[test]
+    fn readable_output() {
+        let config = Config {
+            background: URGB::from([0x64, 0x95, 0xED]),
+            highlight: URGBA::from([0xFF, 0x00, 0x00, 0x80]),
+            target: HCV::RED,
+            palette: vec![Readable(URGB::from([0, 0, 0])), Readable(URGB::WHITE)],
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            json,
+            r##"{"background":"#6495ed","highlight":"#ff000080","target":{"hue":0.0,"chroma":1.0,"value":0.3333333333333333},"palette":["#000000","#ffffff"]}"##
+        );
+        let read: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.background, config.background);
+        assert_eq!(read.highlight, config.highlight);
+        assert_eq!(read.palette, config.palette);
+        assert_approx_eq!(RGB::try_from(read.target).unwrap(), RGB::RED, 0.000_000_001);
+    }
+
+    #[test]
+    fn accepts_compact_and_css() {
+        let json = format!(
+            r#"{{"background":[100,149,237],"highlight":"rgb(255 0 0 / 50%)","target":{},"palette":["cornflowerblue","RGB8(0x64, 0x95, 0xED)"]}}"#,
+            serde_json::to_string(&HCV::<f64>::from(RGB::from([0.2, 0.4, 0.6]))).unwrap()
+        );
+        let config: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(config.background, URGB::from([0x64, 0x95, 0xED]));
+        assert_eq!(config.highlight, URGBA::from([0xFF, 0x00, 0x00, 0x80]));
+        assert_eq!(config.palette, vec![Readable(config.background); 2]);
+        assert_approx_eq!(
+            RGB::try_from(config.target).unwrap(),
+            RGB::from([0.2, 0.4, 0.6]),
+            0.000_000_001
+        );
+    }
+
+    #[test]
+    fn hcv_round_trips() {
+        for rgb in [
+            RGB::<f64>::from([0.2, 0.4, 0.6]),
+            RGB::from([0.9, 0.1, 0.5]),
+            RGB::from([0.5, 0.5, 0.5]),
+            RGB::BLACK,
+            RGB::YELLOW,
+        ]
+        .iter()
+        {
+            let json = serde_json::to_string(&Readable(HCV::from(rgb))).unwrap();
+            let hcv: Readable<HCV<f64>> = serde_json::from_str(&json).unwrap();
+            assert_approx_eq!(RGB::try_from(hcv.0).unwrap(), *rgb, 0.000_000_001);
+        }
+        let grey: Readable<HCV<f64>> =
+            serde_json::from_str(r#"{"hue":null,"chroma":0.0,"value":0.5}"#).unwrap();
+        assert!(grey.0.is_grey());
+        for invalid in [
+            r#"{"hue":null,"chroma":0.5,"value":0.5}"#,
+            r#"{"hue":0.0,"chroma":1.0,"value":0.9}"#,
+            r#"{"hue":0.0,"chroma":1.5,"value":0.5}"#,
+            r##""#ff0000""##,
+        ]
+        .iter()
+        {
+            assert!(serde_json::from_str::<Readable<HCV<f64>>>(invalid).is_err());
+        }
+        // compact (default serde) HCVs are checked too
+        let invalid = HCV::<f64> {
+            sum: 2.7,
+            ..HCV::RED
+        };
+        let json = serde_json::to_string(&invalid).unwrap();
+        assert!(serde_json::from_str::<HCV<f64>>(&json).is_ok());
+        assert!(serde_json::from_str::<Readable<HCV<f64>>>(&json).is_err());
+        assert!(serde_json::from_str::<Readable<URGB<u8>>>(r#""blurple""#).is_err());
+    }
+
+    #[test]
+    fn sixteen_bit_hex() {
+        let urgb = URGB::<u16>::from([0x1234, 0xABCD, 0x0000]);
+        let json = serde_json::to_string(&Readable(urgb)).unwrap();
+        assert_eq!(json, r##""#1234abcd0000""##);
+        let read: Readable<URGB<u16>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.0, urgb);
+        let read: Readable<URGB<u16>> = serde_json::from_str(r##""#ff0000""##).unwrap();
+        assert_eq!(read.0, URGB::RED);
+        let read: Readable<URGB<u16>> = serde_json::from_str("[1, 2, 3]").unwrap();
+        assert_eq!(read.0, URGB::from([1, 2, 3]));
+        let urgba = URGBA::<u16>::from([0x1234, 0xABCD, 0x0000, 0x8000]);
+        let json = serde_json::to_string(&Readable(urgba)).unwrap();
+        assert_eq!(json, r##""#1234abcd00008000""##);
+        let read: Readable<URGBA<u16>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.0, urgba);
+        let json = serde_json::to_string(&Readable(URGBA::<u16>::RED)).unwrap();
+        assert_eq!(json, r##""#ffff00000000""##);
+        let read: Readable<URGBA<u16>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.0, URGBA::RED);
+        for bad in [
+            r##""#1234abcd000""##,
+            r##""#1234abcd00g0""##,
+            r#""blurple""#,
+        ]
+        .iter()
+        {
+            assert!(serde_json::from_str::<Readable<URGB<u16>>>(bad).is_err());
+        }
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright 2021 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
+
+use crate::{
+    cie::{apply_matrix, XYZ_TO_SRGB},
+    clamped, f,
+    linear_rgb::LinearRGB,
+    ColourComponent, RGB, XYZ,
+};
+
+/// The range of colour temperatures (in kelvin) for which the Planckian locus is available.
+pub const PLANCKIAN_LOCUS_RANGE: (f64, f64) = (1_667.0, 25_000.0);
+
+/// The range of correlated colour temperatures (in kelvin) that will be reported.
+/// McCamy's approximation becomes unreliable outside this range.
+pub const CCT_RANGE: (f64, f64) = (2_000.0, 12_500.0);
+
+/// The maximum distance (in CIE 1960 uv) from the Planckian locus for which a correlated
+/// colour temperature is considered meaningful.
+pub const MAX_DUV: f64 = 0.05;
+
+fn in_range<F: ColourComponent>(kelvin: F, range: (f64, f64)) -> bool {
+    kelvin >= f(range.0) && kelvin <= f(range.1)
+}
+
+/// The CIE 1931 xy chromaticity of a black body radiator at the given temperature using
+/// the cubic spline approximation of Kim et al.  Returns `None` if `kelvin` is outside
+/// `PLANCKIAN_LOCUS_RANGE`.
+pub fn planckian_locus_xy<F: ColourComponent>(kelvin: F) -> Option<(F, F)> {
+    if !in_range(kelvin, PLANCKIAN_LOCUS_RANGE) {
+        return None;
+    }
+    let t = kelvin;
+    let x = if t <= f(4_000.0) {
+        f::<F>(-0.266_123_9e9) / t.powi(3) - f::<F>(0.234_358_9e6) / t.powi(2)
+            + f::<F>(0.877_695_6e3) / t
+            + f(0.179_910)
+    } else {
+        f::<F>(-3.025_846_9e9) / t.powi(3)
+            + f::<F>(2.107_037_9e6) / t.powi(2)
+            + f::<F>(0.222_634_7e3) / t
+            + f(0.240_390)
+    };
+    let coefficients = if t <= f(2_222.0) {
+        [-1.106_381_4, -1.348_110_20, 2.185_558_32, -0.202_196_83]
+    } else if t <= f(4_000.0) {
+        [-0.954_947_6, -1.374_185_93, 2.091_370_15, -0.167_488_67]
+    } else {
+        [3.081_758_0, -5.873_386_70, 3.751_129_97, -0.370_014_83]
+    };
+    let y = coefficients
+        .iter()
+        .fold(F::ZERO, |acc, c| acc * x + f::<F>(*c));
+    Some((x, y))
+}
+
+/// McCamy's cubic approximation of the correlated colour temperature of the given
+/// CIE 1931 xy chromaticity.
+pub fn mccamy_cct<F: ColourComponent>(x: F, y: F) -> F {
+    let n = (x - f(0.3320)) / (f::<F>(0.1858) - y);
+    f::<F>(449.0) * n.powi(3) + f::<F>(3525.0) * n.powi(2) + f::<F>(6823.3) * n + f(5520.33)
+}
+
+fn uv<F: ColourComponent>(x: F, y: F) -> (F, F) {
+    let denominator = f::<F>(-2.0) * x + f::<F>(12.0) * y + F::THREE;
+    (F::FOUR * x / denominator, f::<F>(6.0) * y / denominator)
+}
+
+/// The correlated colour temperature (in kelvin) of the colour with the given XYZ
+/// coordinates.  Returns `None` for black, for colours whose CCT is outside `CCT_RANGE`
+/// and for colours that are too far from the Planckian locus (see `MAX_DUV`) for the
+/// concept to be meaningful.
+pub fn correlated_colour_temperature<F: ColourComponent>(xyz: &XYZ<F>) -> Option<F> {
+    let sum = xyz.x + xyz.y + xyz.z;
+    if sum <= F::ZERO {
+        return None;
+    }
+    let (x, y) = (xyz.x / sum, xyz.y / sum);
+    let cct = mccamy_cct(x, y);
+    if !in_range(cct, CCT_RANGE) {
+        return None;
+    }
+    let (locus_x, locus_y) = planckian_locus_xy(cct)?;
+    let (u, v) = uv(x, y);
+    let (locus_u, locus_v) = uv(locus_x, locus_y);
+    if (u - locus_u).hypot(v - locus_v) > f(MAX_DUV) {
+        None
+    } else {
+        Some(cct)
+    }
+}
+
+impl<F: ColourComponent> RGB<F> {
+    /// The colour of a black body radiator at the given temperature scaled so that its
+    /// largest component is one (and clipped to the sRGB gamut).  Returns `None` if
+    /// `kelvin` is outside `PLANCKIAN_LOCUS_RANGE`.
+    pub fn from_kelvin(kelvin: F) -> Option<Self> {
+        let (x, y) = planckian_locus_xy(kelvin)?;
+        let mut array = apply_matrix(&XYZ_TO_SRGB, [x / y, F::ONE, (F::ONE - x - y) / y]);
+        let max = array[0].max(array[1]).max(array[2]);
+        for component in array.iter_mut() {
+            *component = clamped(*component / max);
+        }
+        Some(LinearRGB::from(array).into())
+    }
+}
+
+#[cfg(test)]
+mod temperature_tests {
+    use super::*;
+    use crate::{ColourInterface, HueConstants, RGBConstants};
+    use num_traits_plus::{assert_approx_eq, float_plus::*};
+
+    #[test]
+    fn planckian_locus() {
+        assert!(planckian_locus_xy(1_000.0_f64).is_none());
+        assert!(planckian_locus_xy(30_000.0_f64).is_none());
+        let (x, y) = planckian_locus_xy(2_856.0_f64).unwrap();
+        assert_approx_eq!(x, 0.447_6, 0.001);
+        assert_approx_eq!(y, 0.407_5, 0.001);
+        let (x, y) = planckian_locus_xy(6_500.0_f64).unwrap();
+        assert_approx_eq!(x, 0.313_5, 0.000_5);
+        assert_approx_eq!(y, 0.323_6, 0.000_5);
+    }
+
+    #[test]
+    fn kelvin_to_rgb() {
+        assert!(RGB::<f64>::from_kelvin(1_000.0).is_none());
+        let rgb = RGB::<f64>::from_kelvin(2_000.0).unwrap();
+        assert_eq!(rgb[crate::CCI::Red], 1.0);
+        assert!(rgb[crate::CCI::Green] > rgb[crate::CCI::Blue]);
+        let rgb = RGB::<f64>::from_kelvin(15_000.0).unwrap();
+        assert_eq!(rgb[crate::CCI::Blue], 1.0);
+        assert!(rgb[crate::CCI::Red] < rgb[crate::CCI::Green]);
+    }
+
+    #[test]
+    fn rgb_to_cct() {
+        assert_approx_eq!(
+            RGB::<f64>::WHITE.correlated_colour_temperature().unwrap(),
+            6_504.0,
+            0.001
+        );
+        assert!(RGB::<f64>::BLACK.correlated_colour_temperature().is_none());
+        assert!(RGB::<f64>::RED.correlated_colour_temperature().is_none());
+        assert!(RGB::<f64>::GREEN.correlated_colour_temperature().is_none());
+        for kelvin in (2_500..=8_000).step_by(500) {
+            let rgb = RGB::<f64>::from_kelvin(kelvin as f64).unwrap();
+            let cct = rgb.correlated_colour_temperature().unwrap();
+            assert_approx_eq!(cct, kelvin as f64, 0.005);
+        }
+    }
+}
@@ -9,6 +9,8 @@ use num_traits;
 use num_traits_plus;
 
 use crate::{
+    css::CSSColour,
+    named_colours,
     rgb::{ColourComponent, RGB},
     HueConstants, RGBConstants,
 };
@@ -256,6 +258,10 @@ impl FromStr for URGB<u8> {
             let green = u8::from_str_radix(captures.name("green").unwrap().as_str(), 16)?;
             let blue = u8::from_str_radix(captures.name("blue").unwrap().as_str(), 16)?;
             Ok([red, green, blue].into())
+        } else if let Ok(rgb8) = Self::from_css_str(string) {
+            Ok(rgb8)
+        } else if let Some(rgb8) = named_colours::rgb8_for_name(string) {
+            Ok(rgb8)
         } else {
             Err(URGBError::MalformedText(string.to_string()))
         }
@@ -5,7 +5,8 @@ use std::{ops::Index, str::FromStr};
 use regex::Regex;
 
 use crate::{
-    rgb::ColourComponent, rgba::RGBA, urgb::UnsignedComponent, HueConstants, RGBConstants,
+    css::CSSColour, named_colours, rgb::ColourComponent, rgba::RGBA, urgb::UnsignedComponent,
+    HueConstants, RGBConstants,
 };
 
 #[derive(
@@ -233,6 +234,10 @@ impl FromStr for URGBA<u8> {
             let blue = u8::from_str_radix(captures.name("blue").unwrap().as_str(), 16)?;
             let alpha = u8::from_str_radix(captures.name("alpha").unwrap().as_str(), 16)?;
             Ok([red, green, blue, alpha].into())
+        } else if let Ok(rgba8) = Self::from_css_str(string) {
+            Ok(rgba8)
+        } else if let Some(rgb8) = named_colours::rgb8_for_name(string) {
+            Ok([rgb8[0], rgb8[1], rgb8[2], u8::MAX].into())
         } else {
             Err(URGBAError::MalformedText(string.to_string()))
         }
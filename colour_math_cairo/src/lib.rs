@@ -14,6 +14,7 @@ pub type Size = drawing::Size<f64>;
 pub type TextPosn = drawing::TextPosn<f64>;
 
 pub type RGB = colour_math::RGB<f64>;
+pub type Gradient = colour_math::Gradient<f64>;
 
 pub trait CairoSetColour {
     fn set_source_colour<C: ColourInterface<f64>>(self, colour: &C);
@@ -95,10 +96,10 @@ impl<'a> drawing::Draw<f64> for Drawer<'a> {
         }
     }
 
-    fn paint_linear_gradient(&self, posn: Point, size: Size, colour_stops: &[(RGB, f64)]) {
+    fn paint_linear_gradient(&self, posn: Point, size: Size, gradient: &Gradient) {
         let linear_gradient =
             cairo::LinearGradient::new(0.0, 0.5 * size.height, size.width, 0.5 * size.height);
-        for colour_stop in colour_stops.iter() {
+        for colour_stop in gradient.colour_stops().iter() {
             linear_gradient.add_color_stop_rgb(
                 colour_stop.1,
                 colour_stop.0[CCI::Red],
@@ -1,6 +1,6 @@
 // Copyright 2020 Peter Williams <pwil3058@gmail.com> <pwil3058@bigpond.net.au>
 
-use colour_math::{ColourInterface, Hue, CCI, HCV, RGB, RGBA};
+use colour_math::{ColourInterface, ColourMathError, Hue, CCI, HCV, RGB, RGBA};
 use normalised_angles::Degrees;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -115,6 +115,20 @@ impl ColourInterface<f64> for U8Pixel {
     }
 }
 
+impl U8Pixel {
+    /// As `from(slice)` but with an error (rather than a panic) unless `slice` has
+    /// 3 or 4 elements.
+    pub fn try_from_slice(slice: &[u8]) -> Result<Self, ColourMathError> {
+        match slice.len() {
+            3 | 4 => Ok(slice.into()),
+            length => Err(ColourMathError::BadLength(format!(
+                "pixel: {} elements (expected 3 or 4)",
+                length
+            ))),
+        }
+    }
+}
+
 impl From<&[u8]> for U8Pixel {
     fn from(array: &[u8]) -> Self {
         let rgb: RGB<f64> = array[0..3].into();
@@ -183,6 +197,18 @@ mod tests {
         assert_eq!(pixel, expected);
     }
 
+    #[test]
+    fn try_create_u8_pixel() {
+        let pixel = U8Pixel::try_from_slice(&[1u8, 3, 255, 64]).unwrap();
+        assert_eq!(pixel, U8Pixel::from(&[1u8, 3, 255, 64][..]));
+        for bad in [&[1u8, 3][..], &[1u8, 3, 255, 64, 0][..], &[][..]].iter() {
+            assert!(matches!(
+                U8Pixel::try_from_slice(bad),
+                Err(ColourMathError::BadLength(_))
+            ));
+        }
+    }
+
     #[test]
     fn monochrome_transform() {
         let pixel = U8Pixel::RGB {
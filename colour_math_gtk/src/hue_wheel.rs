@@ -14,7 +14,7 @@ use pw_gix::{
     wrapper::*,
 };
 
-use colour_math::ScalarAttribute;
+use colour_math::{attributes::hue_wheel::Shape, Palette, ScalarAttribute};
 
 use colour_math_cairo::*;
 
@@ -71,6 +71,14 @@ impl GtkHueWheel {
         self.drawing_area.queue_draw();
     }
 
+    pub fn add_palette(&self, palette: &Palette, shape: Shape) {
+        let mut hue_wheel = self.hue_wheel.borrow_mut();
+        for coloured_item in palette.coloured_shapes(shape) {
+            hue_wheel.add_item(coloured_item);
+        }
+        self.drawing_area.queue_draw();
+    }
+
     pub fn remove_item(&self, id: &str) {
         self.hue_wheel.borrow_mut().remove_item(id);
         self.drawing_area.queue_draw();
@@ -18,6 +18,8 @@ pub mod colour {
     pub type ColourManipulatorBuilder = colour_math::manipulator::ColourManipulatorBuilder<f64>;
     pub type ColouredShape = colour_math::attributes::hue_wheel::ColouredShape<f64>;
     pub type HueWheel = colour_math::attributes::hue_wheel::HueWheel<f64>;
+    pub type Pigment = colour_math::pigment::Pigment<f64>;
+    pub type Recipe = colour_math::recipe::Recipe<f64>;
 
     pub fn rgba_from_rgb(rgb: &RGB) -> gdk::RGBA {
         gdk::RGBA {
@@ -11,13 +11,13 @@ use pw_gix::{
     wrapper::*,
 };
 
-use colour_math::{ColourInterface, RGBConstants, CCI};
+use colour_math::{pigment, recipe::RecipeSolver, ColourInterface, RGBConstants, CCI};
 
 use colour_math_cairo::Point;
 
 use crate::{
     angles::Degrees,
-    colour::{ColourManipulator, ColourManipulatorBuilder, RGB},
+    colour::{ColourManipulator, ColourManipulatorBuilder, Pigment, Recipe, RGB},
     coloured::Colourable,
 };
 
@@ -198,6 +198,25 @@ impl ColourManipulatorGUI {
         self.colour_manipulator.borrow().rgb()
     }
 
+    /// Preview the predicted colour of a mixture of paints (with the given weights).
+    /// Returns `false` (leaving the colour unchanged) if no prediction can be made.
+    pub fn set_rgb_for_paint_mixture(&self, paints: &[(&Pigment, f64)]) -> bool {
+        if let Some(rgb) = pigment::mixture_rgb(paints) {
+            self.set_rgb_and_inform(&rgb);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// The mixture of (up to `max_components`) paints from `palette` that best matches
+    /// the current colour (e.g. after auto matching samples).
+    pub fn paint_recipe(&self, palette: &[Pigment], max_components: usize) -> Option<Recipe> {
+        RecipeSolver::new(palette)
+            .max_components(max_components)
+            .solve(&self.rgb())
+    }
+
     pub fn connect_changed<F: Fn(RGB) + 'static>(&self, callback: F) {
         self.change_callbacks.borrow_mut().push(Box::new(callback))
     }